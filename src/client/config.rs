@@ -1,7 +1,112 @@
 use crate::errors::OxpgError;
 use pyo3::{PyErr, PyResult};
+use std::str::FromStr;
 use tokio_postgres::Config;
 
+/// Which kind of server a connection is allowed to land on, mirroring
+/// libpq's `target_session_attrs` connection parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TargetSessionAttrs {
+    Any,
+    ReadWrite,
+    ReadOnly,
+}
+
+impl TargetSessionAttrs {
+    /// Parses the `target_session_attrs` keyword argument, defaulting to
+    /// `any` when the caller didn't pass one explicitly.
+    pub(crate) fn parse(raw: Option<&str>) -> Result<TargetSessionAttrs, OxpgError> {
+        match raw {
+            Some(s) => s.parse(),
+            None => Ok(TargetSessionAttrs::Any),
+        }
+    }
+}
+
+impl FromStr for TargetSessionAttrs {
+    type Err = OxpgError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "any" => Ok(TargetSessionAttrs::Any),
+            "read-write" => Ok(TargetSessionAttrs::ReadWrite),
+            "read-only" => Ok(TargetSessionAttrs::ReadOnly),
+            other => Err(OxpgError::InvalidParameter(format!(
+                "Unknown target_session_attrs '{}': expected one of any, read-write, read-only",
+                other
+            ))),
+        }
+    }
+}
+
+/// Extracts the full ordered list of `(host, port)` candidates a DSN or set
+/// of individual connection parameters resolved to, so callers can attempt
+/// each one in turn until one satisfies `target_session_attrs`.
+pub(crate) fn host_candidates(config: &Config) -> Vec<(String, u16)> {
+    let hosts: Vec<String> = config
+        .get_hosts()
+        .iter()
+        .filter_map(|h| match h {
+            tokio_postgres::config::Host::Tcp(s) => Some(s.clone()),
+            _ => None,
+        })
+        .collect();
+
+    let ports = config.get_ports();
+    hosts
+        .into_iter()
+        .enumerate()
+        .map(|(i, host)| {
+            let port = ports
+                .get(i)
+                .or_else(|| ports.last())
+                .copied()
+                .unwrap_or(5432);
+            (host, port)
+        })
+        .collect()
+}
+
+/// Builds a single-host `Config`, copying over the user/password/dbname
+/// already resolved on `base`, so each failover candidate can be connected
+/// to independently (`tokio_postgres::Config::host` appends rather than
+/// replaces, so attempting candidates one at a time needs its own `Config`
+/// per host).
+pub(crate) fn config_for_host(base: &Config, host: &str, port: u16) -> Config {
+    let mut config = Config::new();
+    config.host(host).port(port);
+
+    if let Some(user) = base.get_user() {
+        config.user(user);
+    }
+    if let Some(password) = base.get_password() {
+        config.password(password);
+    }
+    if let Some(dbname) = base.get_dbname() {
+        config.dbname(dbname);
+    }
+
+    config
+}
+
+/// Pulls the raw `sslmode` value out of a DSN's query string (URI form's
+/// `?sslmode=...`, or keyword/value form's `sslmode=...` token), independent
+/// of `tokio_postgres::Config`'s own parsing. `Config::get_ssl_mode()` only
+/// distinguishes disable/prefer/require, so it silently collapses
+/// `verify-ca`/`verify-full` down to `require` -- reading the DSN text
+/// ourselves keeps that distinction when the caller didn't also pass an
+/// explicit `sslmode` keyword argument.
+pub(crate) fn raw_sslmode_from_dsn(dsn: &str) -> Option<String> {
+    let tokens: Box<dyn Iterator<Item = &str>> = match dsn.split_once('?') {
+        Some((_, query)) => Box::new(query.split('&')),
+        None => Box::new(dsn.split_whitespace()),
+    };
+    tokens
+        .filter_map(|token| token.split_once('='))
+        .find(|(key, _)| *key == "sslmode")
+        .map(|(_, value)| value.trim_matches(['\'', '"']).to_string())
+}
+
 pub(crate) fn extract_host_from_dsn(
     dsn: String,
     config: &mut Config,
@@ -36,6 +141,20 @@ pub(crate) fn extract_host_from_dsn(
     Ok((host, user, port, db, config))
 }
 
+/// Splits a `host` keyword argument of the form `h1:5432,h2:5433` (or
+/// `h1,h2`, falling back to `default_port` for entries without their own)
+/// into `(host, port)` pairs, one `Config::host`/`Config::port` call per
+/// entry -- the same comma-separated multi-host syntax `tokio_postgres::Config`
+/// already accepts inside a DSN, extended to the individual-parameter form.
+fn parse_host_port_list(host: &str, default_port: u16) -> Vec<(String, u16)> {
+    host.split(',')
+        .map(|entry| match entry.rsplit_once(':') {
+            Some((h, p)) => (h.to_string(), p.parse().unwrap_or(default_port)),
+            None => (entry.to_string(), default_port),
+        })
+        .collect()
+}
+
 pub(crate) fn populate_config_from_params(
     host: String,
     user: String,
@@ -44,12 +163,11 @@ pub(crate) fn populate_config_from_params(
     db: String,
     config: &mut Config,
 ) -> &mut Config {
-    config
-        .host(&host)
-        .port(port)
-        .user(&user)
-        .password(&password)
-        .dbname(&db)
+    for (h, p) in parse_host_port_list(&host, port) {
+        config.host(&h).port(p);
+    }
+
+    config.user(&user).password(&password).dbname(&db)
 }
 
 pub(crate) fn validate_connect_params(
@@ -1,185 +1,379 @@
+use crate::client::range::{self, Range, RangeBound};
 use crate::errors::OxpgError;
-use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
 use pyo3::prelude::*;
 use pyo3::types::{
-    PyBool, PyByteArray, PyBytes, PyDate, PyDateTime, PyDelta, PyDict, PyFloat, PyInt, PyNone,
-    PyString, PyTime, PyTuple,
+    PyBool, PyByteArray, PyBytes, PyDate, PyDateTime, PyDelta, PyDict, PyFloat, PyInt, PyList,
+    PyNone, PyString, PyTime, PyTuple, PyTzInfo,
 };
+use std::collections::HashMap;
 use std::error::Error;
 use tokio_postgres::types::private::BytesMut;
-use tokio_postgres::types::{IsNull, ToSql, Type, to_sql_checked};
+use tokio_postgres::types::{FromSql, IsNull, Oid, ToSql, Type, to_sql_checked};
 use tokio_postgres::{Row, Statement};
 
-pub(crate) fn prepare_params<'a>(
-    statement: &Statement,
-    args: &Bound<'a, PyTuple>,
-) -> PyResult<Vec<Box<dyn ToSql + Sync>>> {
-    let mut params: Vec<Box<dyn ToSql + Sync>> = Vec::new();
+/// Grabs a column's raw wire bytes regardless of its Postgres type, for
+/// handing off to a user-registered `register_type` decoder.
+struct RawBytes(Vec<u8>);
 
-    for (idx, arg) in args.iter().enumerate() {
-        let expected_type = statement.params().get(idx);
+impl<'a> FromSql<'a> for RawBytes {
+    fn from_sql(_ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        Ok(RawBytes(raw.to_vec()))
+    }
 
-        if arg.is_instance_of::<PyBool>() {
-            let val: bool = arg.extract().map_err(|e| {
-                OxpgError::InvalidParameter(format!(
-                    "Could not extract BOOL for argument {}: {}",
-                    idx, e
-                ))
-            })?;
-            params.push(Box::new(val));
-        } else if arg.is_instance_of::<PyInt>() {
-            match expected_type {
-                Some(&Type::INT2) => {
-                    let val = arg.extract::<i16>().map_err(|e| {
-                        OxpgError::InvalidParameter(format!(
-                            "Could not fit argument {} into INT2: {}",
-                            idx, e
-                        ))
-                    })?;
-                    params.push(Box::new(val));
-                }
-                Some(&Type::INT4) => {
-                    let val = arg.extract::<i32>().map_err(|e| {
-                        OxpgError::InvalidParameter(format!(
-                            "Could not fit argument {} into INT4: {}",
-                            idx, e
-                        ))
-                    })?;
-                    params.push(Box::new(val));
-                }
-                _ => {
-                    let val = arg.extract::<i64>().map_err(|e| {
-                        OxpgError::InvalidParameter(format!(
-                            "Could not fit argument {} into INT8: {}",
-                            idx, e
-                        ))
-                    })?;
-                    params.push(Box::new(val));
+    fn accepts(_ty: &Type) -> bool {
+        true
+    }
+}
+
+/// Reads a Python `datetime` argument as a UTC-naive `chrono::NaiveDateTime`,
+/// correcting for its `tzinfo` if it carries one rather than silently
+/// mislabeling an aware datetime's wall-clock time as already being UTC.
+/// Naive datetimes (no `tzinfo`) pass through unchanged, on the existing
+/// assumption that they already represent UTC wall-clock time.
+pub(crate) fn datetime_arg_to_utc_naive(arg: &Bound<'_, PyAny>) -> PyResult<NaiveDateTime> {
+    let naive_dt = arg.extract::<NaiveDateTime>()?;
+    let tzinfo = arg.getattr("tzinfo")?;
+    if tzinfo.is_none() {
+        return Ok(naive_dt);
+    }
+    let offset = arg.call_method0("utcoffset")?;
+    if offset.is_none() {
+        return Ok(naive_dt);
+    }
+    let offset = offset.extract::<chrono::Duration>()?;
+    Ok(naive_dt - offset)
+}
+
+fn array_column<'a, T>(
+    py: Python<'a>,
+    row: &Row,
+    idx: usize,
+    column_name: &str,
+) -> PyResult<Bound<'a, PyAny>>
+where
+    T: for<'b> tokio_postgres::types::FromSql<'b>,
+    T: for<'b> IntoPyObject<'b>,
+{
+    let values = row
+        .try_get::<_, Option<Vec<Option<T>>>>(idx)
+        .map_err(|e| {
+            PyErr::from(OxpgError::DataConversionError(format!(
+                "Failed to convert array column '{}': {:?}",
+                column_name, e
+            )))
+        })?;
+
+    match values {
+        None => Ok(PyNone::get(py).to_owned().into_any()),
+        Some(elements) => {
+            let list = PyList::empty(py);
+            for element in elements {
+                match element {
+                    Some(v) => list.append(v).map_err(|e| {
+                        PyErr::from(OxpgError::DataConversionError(format!(
+                            "Failed to append element of array column '{}': {:?}",
+                            column_name, e
+                        )))
+                    })?,
+                    None => list.append(py.None()).map_err(|e| {
+                        PyErr::from(OxpgError::DataConversionError(format!(
+                            "Failed to append NULL element of array column '{}': {:?}",
+                            column_name, e
+                        )))
+                    })?,
                 }
             }
-        } else if arg.is_instance_of::<PyFloat>() {
-            match expected_type {
-                Some(&Type::FLOAT4) => {
-                    let val = arg.extract::<f32>().map_err(|e| {
-                        OxpgError::InvalidParameter(format!(
-                            "Could not extract FLOAT4 for argument {}: {}",
-                            idx, e
-                        ))
-                    })?;
-                    params.push(Box::new(val));
-                }
-                _ => {
-                    let val = arg.extract::<f64>().map_err(|e| {
-                        OxpgError::InvalidParameter(format!(
-                            "Could not extract FLOAT8 for argument {}: {}",
-                            idx, e
-                        ))
-                    })?;
-                    params.push(Box::new(val));
+            Ok(list.into_any())
+        }
+    }
+}
+
+fn uuid_array_to_pyobject(
+    py: Python<'_>,
+    values: Option<Vec<Option<uuid::Uuid>>>,
+    native_types: bool,
+) -> PyResult<Bound<'_, PyAny>> {
+    match values {
+        None => Ok(PyNone::get(py).to_owned().into_any()),
+        Some(elements) => {
+            let list = PyList::empty(py);
+            for element in elements {
+                match element {
+                    Some(u) => list.append(uuid_to_pyobject(py, u, native_types)?)?,
+                    None => list.append(py.None())?,
                 }
             }
-        } else if arg.is_instance_of::<PyString>() {
-            let val: String = arg.extract().map_err(|e| {
-                OxpgError::InvalidParameter(format!(
-                    "Could not extract String for argument {}: {}",
-                    idx, e
-                ))
-            })?;
-            params.push(Box::new(val));
-        } else if arg.is_instance_of::<PyNone>() {
-            match expected_type {
-                Some(&Type::BOOL) => params.push(Box::new(None::<bool>)),
-                Some(&Type::INT2) => params.push(Box::new(None::<i16>)),
-                Some(&Type::INT4) => params.push(Box::new(None::<i32>)),
-                Some(&Type::INT8) => params.push(Box::new(None::<i64>)),
-                Some(&Type::FLOAT4) => params.push(Box::new(None::<f32>)),
-                Some(&Type::FLOAT8) => params.push(Box::new(None::<f64>)),
-                Some(&Type::BYTEA) => params.push(Box::new(None::<Vec<u8>>)),
-                Some(&Type::DATE) => params.push(Box::new(None::<NaiveDate>)),
-                Some(&Type::TIMESTAMP) => params.push(Box::new(None::<chrono::NaiveDateTime>)),
-                Some(&Type::TIMESTAMPTZ) => params.push(Box::new(None::<DateTime<Utc>>)),
-                Some(&Type::TIME) => params.push(Box::new(None::<chrono::NaiveTime>)),
-                Some(&Type::UUID) => params.push(Box::new(None::<uuid::Uuid>)),
-                _ => params.push(Box::new(None::<String>)),
+            Ok(list.into_any())
+        }
+    }
+}
+
+/// Recursively converts a `serde_json::Value` into the native Python object
+/// it represents (`dict`, `list`, `str`, `bool`, `int`/`float`, or `None`)
+/// rather than handing back the JSON text for the caller to re-parse.
+pub(crate) fn json_value_to_pyobject<'a>(
+    py: Python<'a>,
+    value: &serde_json::Value,
+) -> PyResult<Bound<'a, PyAny>> {
+    match value {
+        serde_json::Value::Null => Ok(PyNone::get(py).to_owned().into_any()),
+        serde_json::Value::Bool(b) => Ok(b.into_pyobject(py)?.to_owned().into_any()),
+        serde_json::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Ok(i.into_pyobject(py)?.into_any())
+            } else if let Some(f) = n.as_f64() {
+                Ok(f.into_pyobject(py)?.into_any())
+            } else {
+                Ok(n.to_string().into_pyobject(py)?.into_any())
             }
-        } else if arg.is_instance_of::<PyBytes>() || arg.is_instance_of::<PyByteArray>() {
-            let val: Vec<u8> = arg.extract().map_err(|e| {
-                OxpgError::InvalidParameter(format!(
-                    "Could not extract bytes for argument {}: {}",
-                    idx, e
-                ))
-            })?;
-            params.push(Box::new(val));
-        } else if arg.is_instance_of::<PyDateTime>() {
-            let naive_dt = arg.extract::<chrono::NaiveDateTime>().map_err(|e| {
-                OxpgError::InvalidParameter(format!(
-                    "Could not extract NaiveDateTime for argument {}: {}",
-                    idx, e
-                ))
-            })?;
-            match expected_type {
-                Some(&Type::TIMESTAMP) => params.push(Box::new(naive_dt)),
-                _ => {
-                    let dt_utc = DateTime::<Utc>::from_naive_utc_and_offset(naive_dt, Utc);
-                    params.push(Box::new(dt_utc));
-                }
+        }
+        serde_json::Value::String(s) => Ok(s.into_pyobject(py)?.into_any()),
+        serde_json::Value::Array(items) => {
+            let list = PyList::empty(py);
+            for item in items {
+                list.append(json_value_to_pyobject(py, item)?)?;
             }
-        } else if arg.is_instance_of::<PyDate>() {
-            let date = arg.extract::<chrono::NaiveDate>().map_err(|e| {
-                OxpgError::InvalidParameter(format!(
-                    "Could not extract NaiveDate for argument {}: {}",
-                    idx, e
-                ))
-            })?;
-            params.push(Box::new(date));
-        } else if arg.is_instance_of::<PyTime>() {
-            let time = arg.extract::<chrono::NaiveTime>().map_err(|e| {
-                OxpgError::InvalidParameter(format!(
-                    "Could not extract NaiveTime for argument {}: {}",
-                    idx, e
-                ))
-            })?;
-            params.push(Box::new(time));
-        } else if arg.is_instance_of::<PyDelta>() {
-            let days: i64 = arg.getattr("days")?.extract().map_err(|e| {
-                OxpgError::InvalidParameter(format!(
-                    "Could not extract timedelta.days for argument {}: {}",
-                    idx, e
-                ))
-            })?;
-            let seconds: i64 = arg.getattr("seconds")?.extract().map_err(|e| {
-                OxpgError::InvalidParameter(format!(
-                    "Could not extract timedelta.seconds for argument {}: {}",
-                    idx, e
-                ))
-            })?;
-            let microseconds: i64 = arg.getattr("microseconds")?.extract().map_err(|e| {
-                OxpgError::InvalidParameter(format!(
-                    "Could not extract timedelta.microseconds for argument {}: {}",
-                    idx, e
-                ))
+            Ok(list.into_any())
+        }
+        serde_json::Value::Object(map) => {
+            let dict = PyDict::new(py);
+            for (key, item) in map {
+                dict.set_item(key, json_value_to_pyobject(py, item)?)?;
+            }
+            Ok(dict.into_any())
+        }
+    }
+}
+
+/// Recursively converts a Python `dict`/`list`/scalar into `serde_json::Value`,
+/// the mirror image of `json_value_to_pyobject`, so `dict`/`list` query
+/// parameters can be bound against `json`/`jsonb` columns.
+pub(crate) fn pyobject_to_json_value(value: &Bound<'_, PyAny>) -> PyResult<serde_json::Value> {
+    if value.is_instance_of::<PyNone>() {
+        Ok(serde_json::Value::Null)
+    } else if value.is_instance_of::<PyBool>() {
+        Ok(serde_json::Value::Bool(value.extract()?))
+    } else if value.is_instance_of::<PyInt>() {
+        Ok(serde_json::Value::from(value.extract::<i64>()?))
+    } else if value.is_instance_of::<PyFloat>() {
+        Ok(serde_json::Number::from_f64(value.extract()?)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null))
+    } else if value.is_instance_of::<PyString>() {
+        Ok(serde_json::Value::String(value.extract()?))
+    } else if value.is_instance_of::<PyList>() || value.is_instance_of::<PyTuple>() {
+        let items = value
+            .try_iter()?
+            .map(|item| pyobject_to_json_value(&item?))
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(serde_json::Value::Array(items))
+    } else if value.is_instance_of::<PyDict>() {
+        let dict = value.downcast::<PyDict>()?;
+        let mut map = serde_json::Map::with_capacity(dict.len());
+        for (key, item) in dict.iter() {
+            let key: String = key.extract().map_err(|e| {
+                OxpgError::InvalidParameter(format!("JSON object key must be a string: {}", e))
             })?;
+            map.insert(key, pyobject_to_json_value(&item)?);
+        }
+        Ok(serde_json::Value::Object(map))
+    } else {
+        Err(OxpgError::InvalidParameter(format!(
+            "Cannot convert Python value of type '{}' to JSON",
+            value.get_type().name()?
+        ))
+        .into())
+    }
+}
 
-            let interval_str = format!(
-                "{} days {} seconds {} microseconds",
-                days, seconds, microseconds
-            );
+/// Builds a `decimal.Decimal` from Postgres's own text representation of a
+/// `NUMERIC` value, preserving exactness (going through `f64` would lose
+/// precision on money/decimal columns).
+fn numeric_to_pyobject<'a>(py: Python<'a>, text: &str) -> PyResult<Bound<'a, PyAny>> {
+    py.import("decimal")?.getattr("Decimal")?.call1((text,))
+}
 
-            params.push(Box::new(interval_str));
-        } else {
-            return Err(OxpgError::UnsupportedType(format!(
-                "Parameter at index {} is of type '{}', which is not supported. \
-                 Supported types: int, float, bool, str, bytes, bytearray, datetime, date, time, timedelta, None",
-                idx,
-                arg.get_type().name()?
-            ))
-            .into());
+/// Splits a `decimal.Decimal`'s text form into the pieces Postgres's binary
+/// `NUMERIC` wire format wants: a sign, a `dscale` (digits after the point),
+/// and `digits`, each a base-10000 group read left to right starting at
+/// `weight` groups above the decimal point. Leading/trailing all-zero groups
+/// are trimmed, matching what Postgres itself sends.
+pub(crate) fn decimal_str_to_pg_numeric(text: &str) -> Result<(i16, u16, bool, Vec<i16>), OxpgError> {
+    let invalid = || OxpgError::InvalidParameter(format!("'{}' is not a valid decimal", text));
+
+    let negative = text.starts_with('-');
+    let unsigned = text.trim_start_matches(['+', '-']);
+    let (int_part, frac_part) = match unsigned.split_once('.') {
+        Some((i, f)) => (i, f),
+        None => (unsigned, ""),
+    };
+    if int_part.is_empty() && frac_part.is_empty()
+        || !int_part.bytes().all(|b| b.is_ascii_digit())
+        || !frac_part.bytes().all(|b| b.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+    let scale = frac_part.len() as u16;
+
+    let int_pad = (4 - int_part.len() % 4) % 4;
+    let padded_int = format!("{}{}", "0".repeat(int_pad), int_part);
+    let frac_pad = (4 - frac_part.len() % 4) % 4;
+    let padded_frac = format!("{}{}", frac_part, "0".repeat(frac_pad));
+
+    let weight = (padded_int.len() / 4) as i16 - 1;
+    let mut digits: Vec<i16> = Vec::new();
+    for chunk in padded_int.as_bytes().chunks(4).chain(padded_frac.as_bytes().chunks(4)) {
+        if chunk.is_empty() {
+            continue;
         }
+        let group = std::str::from_utf8(chunk).map_err(|_| invalid())?;
+        digits.push(group.parse::<i16>().map_err(|_| invalid())?);
     }
-    Ok(params)
+
+    let leading_zeros = digits.iter().take_while(|&&d| d == 0).count();
+    let trailing_zeros = digits[leading_zeros..]
+        .iter()
+        .rev()
+        .take_while(|&&d| d == 0)
+        .count();
+    let digits = digits[leading_zeros..digits.len() - trailing_zeros].to_vec();
+    let weight = if digits.is_empty() { 0 } else { weight - leading_zeros as i16 };
+
+    Ok((weight, scale, negative, digits))
+}
+
+/// Encodes a `decimal.Decimal`'s text form as a binary Postgres `NUMERIC`.
+fn encode_numeric(
+    text: &str,
+    out: &mut BytesMut,
+) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+    if text.eq_ignore_ascii_case("nan") {
+        out.extend_from_slice(&0i16.to_be_bytes());
+        out.extend_from_slice(&0i16.to_be_bytes());
+        out.extend_from_slice(&0xC000u16.to_be_bytes());
+        out.extend_from_slice(&0u16.to_be_bytes());
+        return Ok(IsNull::No);
+    }
+
+    let (weight, scale, negative, digits) = decimal_str_to_pg_numeric(text)?;
+    out.extend_from_slice(&(digits.len() as i16).to_be_bytes());
+    out.extend_from_slice(&weight.to_be_bytes());
+    out.extend_from_slice(&(if negative { 0x4000u16 } else { 0u16 }).to_be_bytes());
+    out.extend_from_slice(&scale.to_be_bytes());
+    for digit in &digits {
+        out.extend_from_slice(&digit.to_be_bytes());
+    }
+    Ok(IsNull::No)
+}
+
+/// Writes a 1-D `NUMERIC[]`'s wire representation: the standard Postgres
+/// array header (dimension count, null flag, element OID, then each
+/// dimension's length/lower-bound), followed by each element as either a
+/// `-1` length for `NULL` or a length-prefixed call into `encode_numeric`.
+/// Mirrors the layout the blanket `Vec<Option<T>>: ToSql` impl produces for
+/// the scalar `OwnedParam::Array*` variants, which `NUMERIC` can't use directly
+/// since it has no single fixed-width Rust representation.
+fn encode_numeric_array(
+    values: &[Option<String>],
+    out: &mut BytesMut,
+) -> Result<(), Box<dyn Error + Sync + Send>> {
+    let has_null = values.iter().any(|v| v.is_none());
+    out.extend_from_slice(&1i32.to_be_bytes());
+    out.extend_from_slice(&(has_null as i32).to_be_bytes());
+    out.extend_from_slice(&(Type::NUMERIC.oid() as i32).to_be_bytes());
+    out.extend_from_slice(&(values.len() as i32).to_be_bytes());
+    out.extend_from_slice(&1i32.to_be_bytes());
+
+    for value in values {
+        match value {
+            None => out.extend_from_slice(&(-1i32).to_be_bytes()),
+            Some(text) => {
+                let len_pos = out.len();
+                out.extend_from_slice(&0i32.to_be_bytes());
+                encode_numeric(text, out)?;
+                let len = (out.len() - len_pos - 4) as i32;
+                out[len_pos..len_pos + 4].copy_from_slice(&len.to_be_bytes());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes a rectangular 2-D array's wire representation, reusing each
+/// element's own pre-encoded bytes from `OwnedParam::ArrayNested` (`None`
+/// for a null element). Same header shape as `encode_numeric_array`, but
+/// with two `(length, lower bound)` dimension pairs instead of one.
+fn encode_nested_array(
+    element_oid: Oid,
+    rows: usize,
+    cols: usize,
+    elements: &[Option<Vec<u8>>],
+    out: &mut BytesMut,
+) {
+    let has_null = elements.iter().any(|v| v.is_none());
+    out.extend_from_slice(&2i32.to_be_bytes());
+    out.extend_from_slice(&(has_null as i32).to_be_bytes());
+    out.extend_from_slice(&(element_oid as i32).to_be_bytes());
+    out.extend_from_slice(&(rows as i32).to_be_bytes());
+    out.extend_from_slice(&1i32.to_be_bytes());
+    out.extend_from_slice(&(cols as i32).to_be_bytes());
+    out.extend_from_slice(&1i32.to_be_bytes());
+
+    for element in elements {
+        match element {
+            None => out.extend_from_slice(&(-1i32).to_be_bytes()),
+            Some(bytes) => {
+                out.extend_from_slice(&(bytes.len() as i32).to_be_bytes());
+                out.extend_from_slice(bytes);
+            }
+        }
+    }
+}
+
+/// Builds a `uuid.UUID` from its string form, or returns that string itself
+/// when `native_types` is false.
+fn uuid_to_pyobject<'a>(
+    py: Python<'a>,
+    value: uuid::Uuid,
+    native_types: bool,
+) -> PyResult<Bound<'a, PyAny>> {
+    let text = value.to_string();
+    if native_types {
+        py.import("uuid")?.getattr("UUID")?.call1((text,))
+    } else {
+        Ok(text.into_pyobject(py)?.into_any())
+    }
+}
+
+/// Builds an aware Python `datetime` from a UTC `chrono` timestamp, with
+/// `tzinfo` explicitly set to `datetime.timezone.utc` rather than left
+/// naive, so a `TIMESTAMPTZ` column round-trips as aware on the Python side.
+fn datetime_utc_to_pyobject<'a>(
+    py: Python<'a>,
+    value: DateTime<Utc>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let utc = PyTzInfo::utc(py)?;
+    Ok(PyDateTime::new(
+        py,
+        value.year(),
+        value.month() as u8,
+        value.day() as u8,
+        value.hour() as u8,
+        value.minute() as u8,
+        value.second() as u8,
+        value.timestamp_subsec_micros(),
+        Some(&utc),
+    )?
+    .into_any())
 }
 
-pub(crate) fn row_to_dict<'a>(py: Python<'a>, row: &Row) -> PyResult<Bound<'a, PyDict>> {
+pub(crate) fn row_to_dict<'a>(
+    py: Python<'a>,
+    row: &Row,
+    type_decoders: &HashMap<Oid, Py<PyAny>>,
+    native_types: bool,
+) -> PyResult<Bound<'a, PyDict>> {
     let row_dict = PyDict::new(py);
 
     for (idx, column) in row.columns().iter().enumerate() {
@@ -244,34 +438,28 @@ pub(crate) fn row_to_dict<'a>(py: Python<'a>, row: &Row) -> PyResult<Bound<'a, P
                         e
                     )))
                 })?,
-            Type::JSON | Type::JSONB => row
-                .get::<_, Option<serde_json::Value>>(idx)
-                .map(|v| v.to_string())
-                .into_pyobject(py)
-                .map_err(|e| {
-                    PyErr::from(OxpgError::DataConversionError(format!(
-                        "Failed to convert JSON/JSONB column '{}': {:?}",
-                        column.name(),
-                        e
-                    )))
-                })?,
-            Type::NUMERIC => row
-                .try_get::<_, Option<String>>(idx)
-                .map_err(|e| {
+            Type::JSON | Type::JSONB => {
+                let value = row.get::<_, Option<serde_json::Value>>(idx);
+                match value {
+                    None => PyNone::get(py).to_owned().into_any(),
+                    Some(value) if native_types => json_value_to_pyobject(py, &value)?,
+                    Some(value) => value.to_string().into_pyobject(py)?.into_any(),
+                }
+            }
+            Type::NUMERIC => {
+                let text = row.try_get::<_, Option<String>>(idx).map_err(|e| {
                     PyErr::from(OxpgError::DataConversionError(format!(
                         "Failed to convert NUMERIC column '{}' to string: {:?}",
                         column.name(),
                         e
                     )))
-                })?
-                .into_pyobject(py)
-                .map_err(|e| {
-                    PyErr::from(OxpgError::DataConversionError(format!(
-                        "Failed to convert NUMERIC column '{}' to PyObject: {:?}",
-                        column.name(),
-                        e
-                    )))
-                })?,
+                })?;
+                match text {
+                    None => PyNone::get(py).to_owned().into_any(),
+                    Some(text) if native_types => numeric_to_pyobject(py, &text)?,
+                    Some(text) => text.into_pyobject(py)?.into_any(),
+                }
+            }
             Type::FLOAT4 => row
                 .get::<_, Option<f32>>(idx)
                 .into_pyobject(py)
@@ -322,35 +510,94 @@ pub(crate) fn row_to_dict<'a>(py: Python<'a>, row: &Row) -> PyResult<Bound<'a, P
                         e
                     )))
                 })?,
-            Type::TIMESTAMPTZ => row
-                .get::<_, Option<DateTime<Utc>>>(idx)
-                .into_pyobject(py)
-                .map_err(|e| {
-                    PyErr::from(OxpgError::DataConversionError(format!(
-                        "Failed to convert TIMESTAMPTZ column '{}': {:?}",
-                        column.name(),
-                        e
-                    )))
-                })?,
-            Type::UUID => row
-                .get::<_, Option<uuid::Uuid>>(idx)
-                .map(|u| u.to_string())
-                .into_pyobject(py)
-                .map_err(|e| {
+            Type::TIMESTAMPTZ => match row.get::<_, Option<DateTime<Utc>>>(idx) {
+                None => PyNone::get(py).to_owned().into_any(),
+                Some(dt) => datetime_utc_to_pyobject(py, dt)?,
+            },
+            Type::UUID => {
+                let value = row.get::<_, Option<uuid::Uuid>>(idx);
+                match value {
+                    None => PyNone::get(py).to_owned().into_any(),
+                    Some(u) => uuid_to_pyobject(py, u, native_types)?,
+                }
+            }
+            Type::BOOL_ARRAY => array_column::<bool>(py, row, idx, column.name())?,
+            Type::INT2_ARRAY => array_column::<i16>(py, row, idx, column.name())?,
+            Type::INT4_ARRAY => array_column::<i32>(py, row, idx, column.name())?,
+            Type::INT8_ARRAY => array_column::<i64>(py, row, idx, column.name())?,
+            Type::FLOAT4_ARRAY => array_column::<f32>(py, row, idx, column.name())?,
+            Type::FLOAT8_ARRAY => array_column::<f64>(py, row, idx, column.name())?,
+            Type::TEXT_ARRAY | Type::VARCHAR_ARRAY => {
+                array_column::<String>(py, row, idx, column.name())?
+            }
+            Type::BYTEA_ARRAY => array_column::<Vec<u8>>(py, row, idx, column.name())?,
+            Type::UUID_ARRAY => {
+                let values = row
+                    .try_get::<_, Option<Vec<Option<uuid::Uuid>>>>(idx)
+                    .map_err(|e| {
+                        PyErr::from(OxpgError::DataConversionError(format!(
+                            "Failed to convert UUID[] column '{}': {:?}",
+                            column.name(),
+                            e
+                        )))
+                    })?;
+                uuid_array_to_pyobject(py, values, native_types)?
+            }
+            Type::NUMERIC_ARRAY => array_column::<String>(py, row, idx, column.name())?,
+            Type::INT4RANGE | Type::TSRANGE | Type::TSTZRANGE | Type::DATERANGE => {
+                let raw = row.try_get::<_, Option<RawBytes>>(idx).map_err(|e| {
                     PyErr::from(OxpgError::DataConversionError(format!(
-                        "Failed to convert UUID column '{}': {:?}",
+                        "Failed to read raw bytes for range column '{}': {:?}",
                         column.name(),
                         e
                     )))
-                })?,
-            _ => {
-                return Err(PyErr::from(OxpgError::UnsupportedType(format!(
-                    "Unsupported Postgres type '{}' (OID {}) for column '{}'",
-                    column.type_().name(),
-                    column.type_().oid(),
-                    column.name(),
-                ))));
+                })?;
+                match raw {
+                    None => PyNone::get(py).to_owned().into_any(),
+                    Some(RawBytes(bytes)) => {
+                        let range = match *column.type_() {
+                            Type::INT4RANGE => range::decode_int4range(py, &bytes)?,
+                            Type::TSTZRANGE => range::decode_tstzrange(py, &bytes)?,
+                            Type::DATERANGE => range::decode_daterange(py, &bytes)?,
+                            _ => range::decode_tsrange(py, &bytes)?,
+                        };
+                        Py::new(py, range)?.into_bound(py).into_any()
+                    }
+                }
             }
+            ref other => match type_decoders.get(&other.oid()) {
+                Some(decoder) => {
+                    let raw = row.try_get::<_, Option<RawBytes>>(idx).map_err(|e| {
+                        PyErr::from(OxpgError::DataConversionError(format!(
+                            "Failed to read raw bytes for column '{}': {:?}",
+                            column.name(),
+                            e
+                        )))
+                    })?;
+                    match raw {
+                        Some(RawBytes(bytes)) => {
+                            decoder.call1(py, (PyBytes::new(py, &bytes),))?.into_bound(py)
+                        }
+                        None => PyNone::get(py).to_owned().into_any(),
+                    }
+                }
+                None => {
+                    let raw = row.try_get::<_, Option<RawBytes>>(idx).map_err(|e| {
+                        PyErr::from(OxpgError::DataConversionError(format!(
+                            "Failed to read raw bytes for column '{}': {:?}",
+                            column.name(),
+                            e
+                        )))
+                    })?;
+                    match raw {
+                        None => PyNone::get(py).to_owned().into_any(),
+                        Some(RawBytes(bytes)) => match String::from_utf8(bytes) {
+                            Ok(text) => text.into_pyobject(py)?.into_any(),
+                            Err(e) => PyBytes::new(py, e.as_bytes()).into_any(),
+                        },
+                    }
+                }
+            },
         };
 
         row_dict.set_item(column.name(), value).map_err(|e| {
@@ -380,6 +627,50 @@ pub enum OwnedParam {
     Timestamp(NaiveDateTime),
     TimestampTz(DateTime<Utc>),
     Interval(String),
+    Numeric(String),
+    Uuid(uuid::Uuid),
+
+    ArrayBool(Vec<Option<bool>>),
+    ArrayI16(Vec<Option<i16>>),
+    ArrayI32(Vec<Option<i32>>),
+    ArrayI64(Vec<Option<i64>>),
+    ArrayF32(Vec<Option<f32>>),
+    ArrayF64(Vec<Option<f64>>),
+    ArrayText(Vec<Option<String>>),
+    ArrayUuid(Vec<Option<uuid::Uuid>>),
+    ArrayBytes(Vec<Option<Vec<u8>>>),
+    ArrayNumeric(Vec<Option<String>>),
+
+    /// A rectangular 2-D array parameter (a Python list of lists/tuples).
+    /// Each element is pre-encoded to its own wire bytes at build time
+    /// (`None` for a null element), since the encoding depends on the inner
+    /// scalar type, which is already known by the time this is built --
+    /// unlike the 1-D `Array*` variants above, there's no single Rust type
+    /// to hand to a generic `Vec<Option<T>>::to_sql`.
+    ArrayNested {
+        element_oid: Oid,
+        rows: usize,
+        cols: usize,
+        elements: Vec<Option<Vec<u8>>>,
+    },
+
+    /// A `dict`/`list` query parameter bound for a `json`/`jsonb` column.
+    Json(serde_json::Value),
+
+    /// A Postgres range (`int4range`, `tsrange`), carrying enough of its own
+    /// bound type to encode itself without needing the target column's OID.
+    Range {
+        lower: Option<RangeBound>,
+        upper: Option<RangeBound>,
+        lower_inclusive: bool,
+        upper_inclusive: bool,
+        empty: bool,
+    },
+
+    /// A Python value oxpg doesn't natively know how to encode, held onto
+    /// until `refine_params` knows the target column's OID and can hand it
+    /// to a `register_type_encoder`-registered callable.
+    Custom(Py<PyAny>),
 
     NullBool,
     NullI16,
@@ -422,6 +713,52 @@ impl ToSql for OwnedParam {
             OwnedParam::Timestamp(v) => v.to_sql(ty, out),
             OwnedParam::TimestampTz(v) => v.to_sql(ty, out),
             OwnedParam::Interval(v) => v.to_sql(ty, out),
+            OwnedParam::Numeric(v) => encode_numeric(v, out),
+            OwnedParam::Uuid(v) => v.to_sql(ty, out),
+
+            OwnedParam::ArrayBool(v) => v.to_sql(ty, out),
+            OwnedParam::ArrayI16(v) => v.to_sql(ty, out),
+            OwnedParam::ArrayI32(v) => v.to_sql(ty, out),
+            OwnedParam::ArrayI64(v) => v.to_sql(ty, out),
+            OwnedParam::ArrayF32(v) => v.to_sql(ty, out),
+            OwnedParam::ArrayF64(v) => v.to_sql(ty, out),
+            OwnedParam::ArrayText(v) => v.to_sql(ty, out),
+            OwnedParam::ArrayUuid(v) => v.to_sql(ty, out),
+            OwnedParam::ArrayBytes(v) => v.to_sql(ty, out),
+            OwnedParam::ArrayNumeric(values) => {
+                encode_numeric_array(values, out)?;
+                Ok(IsNull::No)
+            }
+
+            OwnedParam::ArrayNested {
+                element_oid,
+                rows,
+                cols,
+                elements,
+            } => {
+                encode_nested_array(*element_oid, *rows, *cols, elements, out);
+                Ok(IsNull::No)
+            }
+
+            OwnedParam::Json(v) => v.to_sql(ty, out),
+
+            OwnedParam::Range {
+                lower,
+                upper,
+                lower_inclusive,
+                upper_inclusive,
+                empty,
+            } => {
+                range::encode(lower, upper, *lower_inclusive, *upper_inclusive, *empty, out);
+                Ok(IsNull::No)
+            }
+
+            OwnedParam::Custom(_) => Err(
+                "unresolved custom parameter: no register_type_encoder was registered \
+                 for this column's type"
+                    .to_string()
+                    .into(),
+            ),
 
             OwnedParam::NullBool => None::<bool>.to_sql(ty, out),
             OwnedParam::NullI16 => None::<i16>.to_sql(ty, out),
@@ -446,6 +783,271 @@ impl ToSql for OwnedParam {
     to_sql_checked!();
 }
 
+/// Builds an `OwnedParam::Array*` variant from a Python list/tuple, inferring
+/// the element type from the first non-`None` element. `refine_params` later
+/// narrows the variant (e.g. `ArrayI64` -> `ArrayI16`) once the prepared
+/// statement's expected array OID is known. A first element that's itself a
+/// list/tuple is handled one level deep as a rectangular 2-D array
+/// (`OwnedParam::ArrayNested`), whose elements may only be bool, int, float,
+/// str or bytes (`Decimal`/`UUID` elements are rejected there, unlike in a
+/// flat array) -- `refine_params`' OID-driven narrowing only applies to
+/// 1-D arrays, so a nested array's element type can't be retargeted later.
+fn build_array_param(arg: &Bound<'_, PyAny>, idx: usize) -> Result<OwnedParam, OxpgError> {
+    let elements: Vec<Bound<'_, PyAny>> = arg
+        .try_iter()
+        .map_err(|e| OxpgError::InvalidParameter(format!("Array arg {}: {}", idx, e)))?
+        .collect::<PyResult<Vec<_>>>()
+        .map_err(|e| OxpgError::InvalidParameter(format!("Array arg {}: {}", idx, e)))?;
+
+    let sample = elements.iter().find(|e| !e.is_instance_of::<PyNone>());
+
+    let Some(sample) = sample else {
+        // Empty array, or all-NULL: default to TEXT[] and let refine_params
+        // retarget it once the expected column type is known.
+        return Ok(OwnedParam::ArrayText(vec![None; elements.len()]));
+    };
+
+    if sample.is_instance_of::<PyBool>() {
+        let values = elements
+            .iter()
+            .map(|e| {
+                if e.is_instance_of::<PyNone>() {
+                    Ok(None)
+                } else {
+                    e.extract::<bool>().map(Some)
+                }
+            })
+            .collect::<PyResult<Vec<_>>>()
+            .map_err(|e| OxpgError::InvalidParameter(format!("Array arg {}: {}", idx, e)))?;
+        Ok(OwnedParam::ArrayBool(values))
+    } else if sample.is_instance_of::<PyInt>() {
+        let values = elements
+            .iter()
+            .map(|e| {
+                if e.is_instance_of::<PyNone>() {
+                    Ok(None)
+                } else {
+                    e.extract::<i64>().map(Some)
+                }
+            })
+            .collect::<PyResult<Vec<_>>>()
+            .map_err(|e| OxpgError::InvalidParameter(format!("Array arg {}: {}", idx, e)))?;
+        Ok(OwnedParam::ArrayI64(values))
+    } else if sample.is_instance_of::<PyFloat>() {
+        let values = elements
+            .iter()
+            .map(|e| {
+                if e.is_instance_of::<PyNone>() {
+                    Ok(None)
+                } else {
+                    e.extract::<f64>().map(Some)
+                }
+            })
+            .collect::<PyResult<Vec<_>>>()
+            .map_err(|e| OxpgError::InvalidParameter(format!("Array arg {}: {}", idx, e)))?;
+        Ok(OwnedParam::ArrayF64(values))
+    } else if sample.is_instance_of::<PyString>() {
+        let values = elements
+            .iter()
+            .map(|e| {
+                if e.is_instance_of::<PyNone>() {
+                    Ok(None)
+                } else {
+                    e.extract::<String>().map(Some)
+                }
+            })
+            .collect::<PyResult<Vec<_>>>()
+            .map_err(|e| OxpgError::InvalidParameter(format!("Array arg {}: {}", idx, e)))?;
+        Ok(OwnedParam::ArrayText(values))
+    } else if sample.is_instance_of::<PyBytes>() || sample.is_instance_of::<PyByteArray>() {
+        let values = elements
+            .iter()
+            .map(|e| {
+                if e.is_instance_of::<PyNone>() {
+                    Ok(None)
+                } else {
+                    e.extract::<Vec<u8>>().map(Some)
+                }
+            })
+            .collect::<PyResult<Vec<_>>>()
+            .map_err(|e| OxpgError::InvalidParameter(format!("Array arg {}: {}", idx, e)))?;
+        Ok(OwnedParam::ArrayBytes(values))
+    } else if sample.get_type().name()?.to_string() == "Decimal" {
+        // Same duck-typing `extract_params` uses for a scalar `Decimal`:
+        // its string form is handed to `encode_numeric`, with the same
+        // restriction to plain (non-exponent) notation.
+        let values = elements
+            .iter()
+            .map(|e| {
+                if e.is_instance_of::<PyNone>() {
+                    Ok(None)
+                } else {
+                    Ok(Some(e.str()?.to_string()))
+                }
+            })
+            .collect::<PyResult<Vec<_>>>()
+            .map_err(|e| OxpgError::InvalidParameter(format!("Array arg {}: {}", idx, e)))?;
+        Ok(OwnedParam::ArrayNumeric(values))
+    } else if sample.get_type().name()?.to_string() == "UUID" {
+        // Same duck-typing `extract_params` uses for a scalar `UUID`.
+        let values = elements
+            .iter()
+            .map(|e| {
+                if e.is_instance_of::<PyNone>() {
+                    Ok(None)
+                } else {
+                    e.str()?.to_string().parse::<uuid::Uuid>().map(Some).map_err(|err| {
+                        PyErr::from(OxpgError::InvalidParameter(format!(
+                            "Array arg {}: {}",
+                            idx, err
+                        )))
+                    })
+                }
+            })
+            .collect::<PyResult<Vec<_>>>()
+            .map_err(|e| OxpgError::InvalidParameter(format!("Array arg {}: {}", idx, e)))?;
+        Ok(OwnedParam::ArrayUuid(values))
+    } else if sample.is_instance_of::<PyList>() || sample.is_instance_of::<PyTuple>() {
+        build_nested_array_param(&elements, idx)
+    } else {
+        Err(OxpgError::UnsupportedType(format!(
+            "Array argument {} has unsupported element type '{}'",
+            idx,
+            sample.get_type().name()?
+        )))
+    }
+}
+
+/// Builds an `OwnedParam::ArrayNested` from a Python list/tuple of
+/// lists/tuples, one level deep. Requires every row to be the same length
+/// (Postgres arrays are always rectangular) and every non-`None` element
+/// across all rows to share one of bool, int, float, str or bytes; the
+/// `Decimal`/`UUID` elements `build_array_param` accepts in a flat array
+/// are not supported here.
+fn build_nested_array_param(
+    elements: &[Bound<'_, PyAny>],
+    idx: usize,
+) -> Result<OwnedParam, OxpgError> {
+    let rows: Vec<Vec<Bound<'_, PyAny>>> = elements
+        .iter()
+        .map(|row| {
+            row.try_iter()
+                .and_then(|it| it.collect::<PyResult<Vec<_>>>())
+        })
+        .collect::<PyResult<Vec<_>>>()
+        .map_err(|e| OxpgError::InvalidParameter(format!("Array arg {}: {}", idx, e)))?;
+
+    let cols = rows.first().map_or(0, |r| r.len());
+    if rows.iter().any(|r| r.len() != cols) {
+        return Err(OxpgError::InvalidParameter(format!(
+            "Array arg {}: nested array rows must all be the same length",
+            idx
+        )));
+    }
+
+    let inner_sample = rows.iter().flatten().find(|e| !e.is_instance_of::<PyNone>());
+
+    let Some(inner_sample) = inner_sample else {
+        return Ok(OwnedParam::ArrayNested {
+            element_oid: Type::TEXT.oid(),
+            rows: rows.len(),
+            cols,
+            elements: vec![None; rows.len() * cols],
+        });
+    };
+
+    enum NestedKind {
+        Bool,
+        Int,
+        Float,
+        Text,
+        Bytes,
+    }
+
+    let kind = if inner_sample.is_instance_of::<PyBool>() {
+        NestedKind::Bool
+    } else if inner_sample.is_instance_of::<PyInt>() {
+        NestedKind::Int
+    } else if inner_sample.is_instance_of::<PyFloat>() {
+        NestedKind::Float
+    } else if inner_sample.is_instance_of::<PyString>() {
+        NestedKind::Text
+    } else if inner_sample.is_instance_of::<PyBytes>() || inner_sample.is_instance_of::<PyByteArray>() {
+        NestedKind::Bytes
+    } else {
+        return Err(OxpgError::UnsupportedType(format!(
+            "Array argument {}: nested element type '{}' is not supported",
+            idx,
+            inner_sample.get_type().name()?
+        )));
+    };
+
+    let element_oid = match kind {
+        NestedKind::Bool => Type::BOOL.oid(),
+        NestedKind::Int => Type::INT8.oid(),
+        NestedKind::Float => Type::FLOAT8.oid(),
+        NestedKind::Text => Type::TEXT.oid(),
+        NestedKind::Bytes => Type::BYTEA.oid(),
+    };
+
+    let mut out_elements = Vec::with_capacity(rows.len() * cols);
+    for row in &rows {
+        for el in row {
+            if el.is_instance_of::<PyNone>() {
+                out_elements.push(None);
+                continue;
+            }
+            let mut buf = BytesMut::new();
+            let encoded = match kind {
+                NestedKind::Bool => {
+                    let v: bool = el.extract().map_err(|e| {
+                        OxpgError::InvalidParameter(format!("Array arg {}: {}", idx, e))
+                    })?;
+                    v.to_sql(&Type::BOOL, &mut buf)
+                }
+                NestedKind::Int => {
+                    let v: i64 = el.extract().map_err(|e| {
+                        OxpgError::InvalidParameter(format!("Array arg {}: {}", idx, e))
+                    })?;
+                    v.to_sql(&Type::INT8, &mut buf)
+                }
+                NestedKind::Float => {
+                    let v: f64 = el.extract().map_err(|e| {
+                        OxpgError::InvalidParameter(format!("Array arg {}: {}", idx, e))
+                    })?;
+                    v.to_sql(&Type::FLOAT8, &mut buf)
+                }
+                NestedKind::Text => {
+                    let v: String = el.extract().map_err(|e| {
+                        OxpgError::InvalidParameter(format!("Array arg {}: {}", idx, e))
+                    })?;
+                    v.to_sql(&Type::TEXT, &mut buf)
+                }
+                NestedKind::Bytes => {
+                    let v: Vec<u8> = el.extract().map_err(|e| {
+                        OxpgError::InvalidParameter(format!("Array arg {}: {}", idx, e))
+                    })?;
+                    v.to_sql(&Type::BYTEA, &mut buf)
+                }
+            };
+            encoded.map_err(|e| {
+                OxpgError::InvalidParameter(format!(
+                    "Array arg {}: failed to encode nested element: {}",
+                    idx, e
+                ))
+            })?;
+            out_elements.push(Some(buf.to_vec()));
+        }
+    }
+
+    Ok(OwnedParam::ArrayNested {
+        element_oid,
+        rows: rows.len(),
+        cols,
+        elements: out_elements,
+    })
+}
+
 pub(crate) fn extract_params(args: &Bound<PyTuple>) -> PyResult<Vec<OwnedParam>> {
     let mut params = Vec::with_capacity(args.len());
 
@@ -481,10 +1083,9 @@ pub(crate) fn extract_params(args: &Bound<PyTuple>) -> PyResult<Vec<OwnedParam>>
                 })?,
             )
         } else if arg.is_instance_of::<PyDateTime>() {
-            let naive_dt = arg
-                .extract::<NaiveDateTime>()
+            let utc_naive = datetime_arg_to_utc_naive(&arg)
                 .map_err(|e| OxpgError::InvalidParameter(format!("DateTime arg {}: {}", idx, e)))?;
-            OwnedParam::TimestampTz(DateTime::<Utc>::from_naive_utc_and_offset(naive_dt, Utc))
+            OwnedParam::TimestampTz(DateTime::<Utc>::from_naive_utc_and_offset(utc_naive, Utc))
         } else if arg.is_instance_of::<PyDate>() {
             OwnedParam::Date(
                 arg.extract()
@@ -509,13 +1110,44 @@ pub(crate) fn extract_params(args: &Bound<PyTuple>) -> PyResult<Vec<OwnedParam>>
                 "{} days {} seconds {} microseconds",
                 days, seconds, microseconds
             ))
+        } else if arg.get_type().name()?.to_string() == "Decimal" {
+            // There's no dedicated pyo3 type for `decimal.Decimal`; its own
+            // text form is parsed by `encode_numeric` when bound, which takes
+            // plain decimal notation or `NaN` but not an exponent (`1E-7`).
+            OwnedParam::Numeric(arg.str()?.to_string())
+        } else if arg.get_type().name()?.to_string() == "UUID" {
+            // Likewise for `uuid.UUID`: its string form round-trips cleanly
+            // through `uuid::Uuid::parse_str`.
+            OwnedParam::Uuid(arg.str()?.to_string().parse().map_err(|e| {
+                OxpgError::InvalidParameter(format!("UUID arg {}: {}", idx, e))
+            })?)
+        } else if arg.is_instance_of::<PyDict>() {
+            OwnedParam::Json(pyobject_to_json_value(&arg).map_err(|e| {
+                OxpgError::InvalidParameter(format!("JSON arg {}: {}", idx, e))
+            })?)
+        } else if arg.is_instance_of::<PyList>() || arg.is_instance_of::<PyTuple>() {
+            build_array_param(&arg, idx)?
+        } else if let Ok(range) = arg.extract::<PyRef<Range>>() {
+            let py = arg.py();
+            let bound = |value: &Option<Py<PyAny>>| -> PyResult<Option<RangeBound>> {
+                value
+                    .as_ref()
+                    .map(|v| range::bound_from_pyobject(v.bind(py)))
+                    .transpose()
+            };
+            OwnedParam::Range {
+                lower: bound(&range.lower)
+                    .map_err(|e| OxpgError::InvalidParameter(format!("Range arg {}: {}", idx, e)))?,
+                upper: bound(&range.upper)
+                    .map_err(|e| OxpgError::InvalidParameter(format!("Range arg {}: {}", idx, e)))?,
+                lower_inclusive: range.lower_inclusive,
+                upper_inclusive: range.upper_inclusive,
+                empty: range.empty,
+            }
         } else {
-            return Err(OxpgError::UnsupportedType(format!(
-                "Parameter at index {} is of type '{}', which is not supported. \
-                 Supported types: int, float, bool, str, bytes, bytearray, datetime, date, time, timedelta, None",
-                idx,
-                arg.get_type().name()?
-            )).into());
+            // Might still be encodable by a `register_type_encoder` callable
+            // once `refine_params` knows the target column's OID.
+            OwnedParam::Custom(arg.clone().unbind())
         };
 
         params.push(param);
@@ -524,12 +1156,45 @@ pub(crate) fn extract_params(args: &Bound<PyTuple>) -> PyResult<Vec<OwnedParam>>
     Ok(params)
 }
 
-pub(crate) fn refine_params(params: &mut Vec<OwnedParam>, statement: &Statement) {
+pub(crate) fn refine_params(
+    py: Python<'_>,
+    params: &mut [OwnedParam],
+    statement: &Statement,
+    type_encoders: &HashMap<Oid, Py<PyAny>>,
+    type_encoders_by_name: &HashMap<String, Py<PyAny>>,
+) -> PyResult<()> {
     for (idx, param) in params.iter_mut().enumerate() {
         let Some(expected) = statement.params().get(idx) else {
             continue;
         };
 
+        if let OwnedParam::Custom(obj) = param {
+            let type_name = obj.bind(py).get_type().name()?.to_string();
+            let encoder = type_encoders
+                .get(&expected.oid())
+                .or_else(|| type_encoders_by_name.get(&type_name));
+            let Some(encoder) = encoder else {
+                return Err(OxpgError::UnsupportedType(format!(
+                    "Parameter at index {} is of type '{}', which oxpg doesn't support for \
+                     column type '{}' (OID {}). Register an encoder with \
+                     Client.register_type_encoder() or Client.register_type_encoder_for_type().",
+                    idx,
+                    type_name,
+                    expected.name(),
+                    expected.oid(),
+                ))
+                .into());
+            };
+
+            let encoded = encoder.call1(py, (obj.clone_ref(py),))?;
+            *param = if let Ok(text) = encoded.extract::<String>(py) {
+                OwnedParam::Text(text)
+            } else {
+                OwnedParam::Bytes(encoded.extract::<Vec<u8>>(py)?)
+            };
+            continue;
+        }
+
         *param = match (&param, expected) {
             (OwnedParam::I64(v), &Type::INT2) => OwnedParam::I16(*v as i16),
             (OwnedParam::I64(v), &Type::INT4) => OwnedParam::I32(*v as i32),
@@ -553,7 +1218,82 @@ pub(crate) fn refine_params(params: &mut Vec<OwnedParam>, statement: &Statement)
             (OwnedParam::NullText, &Type::TIMESTAMPTZ) => OwnedParam::NullTimestampTz,
             (OwnedParam::NullText, &Type::UUID) => OwnedParam::NullUuid,
 
+            (OwnedParam::ArrayI64(v), &Type::INT2_ARRAY) => {
+                OwnedParam::ArrayI16(v.iter().map(|e| e.map(|n| n as i16)).collect())
+            }
+            (OwnedParam::ArrayI64(v), &Type::INT4_ARRAY) => {
+                OwnedParam::ArrayI32(v.iter().map(|e| e.map(|n| n as i32)).collect())
+            }
+            (OwnedParam::ArrayF64(v), &Type::FLOAT4_ARRAY) => {
+                OwnedParam::ArrayF32(v.iter().map(|e| e.map(|n| n as f32)).collect())
+            }
+            (OwnedParam::ArrayText(v), &Type::BOOL_ARRAY) if v.iter().all(Option::is_none) => {
+                OwnedParam::ArrayBool(vec![None; v.len()])
+            }
+            (OwnedParam::ArrayText(v), &Type::INT2_ARRAY) if v.iter().all(Option::is_none) => {
+                OwnedParam::ArrayI16(vec![None; v.len()])
+            }
+            (OwnedParam::ArrayText(v), &Type::INT4_ARRAY) if v.iter().all(Option::is_none) => {
+                OwnedParam::ArrayI32(vec![None; v.len()])
+            }
+            (OwnedParam::ArrayText(v), &Type::INT8_ARRAY) if v.iter().all(Option::is_none) => {
+                OwnedParam::ArrayI64(vec![None; v.len()])
+            }
+            (OwnedParam::ArrayText(v), &Type::FLOAT4_ARRAY) if v.iter().all(Option::is_none) => {
+                OwnedParam::ArrayF32(vec![None; v.len()])
+            }
+            (OwnedParam::ArrayText(v), &Type::FLOAT8_ARRAY) if v.iter().all(Option::is_none) => {
+                OwnedParam::ArrayF64(vec![None; v.len()])
+            }
+            (OwnedParam::ArrayText(v), &Type::UUID_ARRAY) => {
+                let parsed: Result<Vec<Option<uuid::Uuid>>, _> = v
+                    .iter()
+                    .map(|e| e.as_deref().map(str::parse).transpose())
+                    .collect();
+                match parsed {
+                    Ok(values) => OwnedParam::ArrayUuid(values),
+                    Err(_) => continue,
+                }
+            }
+
+            // A Python list bound for a json/jsonb column: build_array_param
+            // already inferred a scalar array variant, so re-wrap it as JSON
+            // instead of sending a real Postgres array.
+            (OwnedParam::ArrayBool(v), &Type::JSON) | (OwnedParam::ArrayBool(v), &Type::JSONB) => {
+                OwnedParam::Json(serde_json::Value::Array(
+                    v.iter()
+                        .map(|e| e.map_or(serde_json::Value::Null, serde_json::Value::Bool))
+                        .collect(),
+                ))
+            }
+            (OwnedParam::ArrayI64(v), &Type::JSON) | (OwnedParam::ArrayI64(v), &Type::JSONB) => {
+                OwnedParam::Json(serde_json::Value::Array(
+                    v.iter()
+                        .map(|e| e.map_or(serde_json::Value::Null, serde_json::Value::from))
+                        .collect(),
+                ))
+            }
+            (OwnedParam::ArrayF64(v), &Type::JSON) | (OwnedParam::ArrayF64(v), &Type::JSONB) => {
+                OwnedParam::Json(serde_json::Value::Array(
+                    v.iter()
+                        .map(|e| {
+                            e.and_then(serde_json::Number::from_f64)
+                                .map_or(serde_json::Value::Null, serde_json::Value::Number)
+                        })
+                        .collect(),
+                ))
+            }
+            (OwnedParam::ArrayText(v), &Type::JSON) | (OwnedParam::ArrayText(v), &Type::JSONB) => {
+                OwnedParam::Json(serde_json::Value::Array(
+                    v.iter()
+                        .map(|e| e.clone().map_or(serde_json::Value::Null, serde_json::Value::String))
+                        .collect(),
+                ))
+            }
+
             _ => continue,
         };
     }
+
+    Ok(())
 }
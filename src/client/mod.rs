@@ -1,20 +1,408 @@
 mod config;
 mod conversions;
+mod notify;
+mod pool;
+mod range;
+mod tls;
+mod transaction;
 
 #[cfg(test)]
 mod tests;
 
-use std::sync::Arc;
+pub use notify::{Notification, NotificationStream};
+pub use pool::{connect_pool, Pool, PooledConnection};
+pub use range::Range;
+pub use transaction::Transaction;
 
-use crate::client::config::validate_connect_params;
+use std::collections::{HashMap, VecDeque};
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+
+use crate::client::config::{validate_connect_params, TargetSessionAttrs};
 use crate::client::conversions::{extract_params, refine_params};
+use crate::client::notify::NotificationBuffer;
+use crate::client::tls::SslMode;
 use crate::errors::OxpgError;
+use lru::LruCache;
 use pyo3::prelude::*;
 use pyo3::types::{PyList, PyTuple};
 use pyo3_async_runtimes::tokio::future_into_py;
 use pyo3_stub_gen::derive::*;
-use tokio_postgres::types::ToSql;
-use tokio_postgres::{Client as PgClient, Config};
+use tokio_postgres::types::{Oid, ToSql};
+use tokio_postgres::{Client as PgClient, Config, Statement};
+
+pub(crate) type TypeHandlers = Arc<Mutex<HashMap<Oid, Py<PyAny>>>>;
+/// Like `TypeHandlers`, but keyed by the Python type's own name instead of a
+/// Postgres OID, so an encoder can be resolved from the parameter's type
+/// even when the target column's OID didn't already have one registered.
+pub(crate) type TypeHandlersByName = Arc<Mutex<HashMap<String, Py<PyAny>>>>;
+type StatementCache = Arc<Mutex<LruCache<String, Arc<Statement>>>>;
+
+const DEFAULT_STATEMENT_CACHE_SIZE: usize = 128;
+
+/// Prepares `query` against `client`, reusing an already-prepared
+/// `Statement` from `cache` when the exact SQL text was seen before. A
+/// cache hit also skips re-describing the statement's parameter/result
+/// type metadata, since the cached `Statement` already carries it.
+async fn prepare_cached(
+    client: &PgClient,
+    cache: &StatementCache,
+    query: &str,
+) -> Result<Arc<Statement>, tokio_postgres::Error> {
+    if let Some(statement) = cache.lock().unwrap().get(query) {
+        return Ok(statement.clone());
+    }
+
+    let statement = Arc::new(client.prepare(query).await?);
+    cache.lock().unwrap().put(query.to_string(), statement.clone());
+    Ok(statement)
+}
+
+/// Whether `err` is a connection-level I/O error of a kind that's worth
+/// retrying (the server briefly refusing/resetting connections during
+/// startup), as opposed to a permanent failure like bad credentials or an
+/// invalid config, which should fail immediately.
+fn is_transient_io_error(err: &tokio_postgres::Error) -> bool {
+    use std::error::Error as StdError;
+
+    err.source()
+        .and_then(|e| e.downcast_ref::<std::io::Error>())
+        .is_some_and(|io_err| {
+            matches!(
+                io_err.kind(),
+                std::io::ErrorKind::ConnectionRefused
+                    | std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+            )
+        })
+}
+
+/// Connects with `config`, retrying transient I/O failures with exponential
+/// backoff (`retry_backoff * 2^attempt` seconds between attempts, up to
+/// `max_retries` retries). Authentication/config errors are not transient
+/// and are returned immediately.
+async fn connect_with_retry<T>(
+    config: &Config,
+    tls: T,
+    max_retries: u32,
+    retry_backoff: f64,
+) -> Result<
+    (
+        tokio_postgres::Client,
+        tokio_postgres::Connection<tokio_postgres::Socket, T::Stream>,
+    ),
+    tokio_postgres::Error,
+>
+where
+    T: tokio_postgres::tls::MakeTlsConnect<tokio_postgres::Socket> + Clone,
+{
+    let mut attempt = 0u32;
+    loop {
+        match config.connect(tls.clone()).await {
+            Ok(result) => return Ok(result),
+            Err(e) if attempt < max_retries && is_transient_io_error(&e) => {
+                let delay = retry_backoff * 2f64.powi(attempt as i32);
+                tokio::time::sleep(std::time::Duration::from_secs_f64(delay)).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Drives `connection`'s background I/O on `runtime` until it closes,
+/// forwarding any `NOTIFY` messages into `notifications` (when the caller
+/// wants them buffered) so they aren't silently dropped between queries.
+fn spawn_connection_driver<T>(
+    runtime: &tokio::runtime::Handle,
+    mut connection: tokio_postgres::Connection<tokio_postgres::Socket, T>,
+    notifications: Option<NotificationBuffer>,
+) where
+    T: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static,
+{
+    runtime.spawn(async move {
+        loop {
+            match std::future::poll_fn(|cx| connection.poll_message(cx)).await {
+                Some(Ok(tokio_postgres::AsyncMessage::Notification(n))) => {
+                    if let Some(buffer) = &notifications {
+                        buffer
+                            .lock()
+                            .unwrap()
+                            .push_back(notify::Notification::from_pg(&n));
+                    }
+                }
+                Some(Ok(_)) => {}
+                Some(Err(_)) => break,
+                None => break,
+            }
+        }
+    });
+}
+
+/// Opens a single connection honoring `sslmode`/retry settings, spawning its
+/// background I/O driver onto `runtime` and returning the resulting client.
+/// Shared between `connect` and `Pool`, which both need to mint
+/// connections the same way. `notifications`, when given, receives any
+/// `NOTIFY` messages the server sends on this connection.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn open_connection(
+    config: &Config,
+    sslmode: SslMode,
+    sslrootcert: Option<&str>,
+    sslcert: Option<&str>,
+    sslkey: Option<&str>,
+    runtime: &tokio::runtime::Handle,
+    max_retries: u32,
+    retry_backoff: f64,
+    notifications: Option<NotificationBuffer>,
+) -> PyResult<Arc<PgClient>> {
+    // `tokio_postgres` decides from the `Config`'s own `ssl_mode` whether a
+    // server that declines TLS gets a plaintext session: only `prefer` may
+    // fall back, every stricter mode insists on TLS.
+    let mut config = config.clone();
+    config.ssl_mode(match sslmode {
+        SslMode::Disable => tokio_postgres::config::SslMode::Disable,
+        SslMode::Prefer => tokio_postgres::config::SslMode::Prefer,
+        _ => tokio_postgres::config::SslMode::Require,
+    });
+    let config = &config;
+
+    let client = if sslmode == SslMode::Disable {
+        let (client, connection) =
+            connect_with_retry(config, tokio_postgres::NoTls, max_retries, retry_backoff)
+                .await
+                .map_err(|e| {
+                    PyErr::from(OxpgError::ConnectionFailed(format!(
+                        "Failed to connect to PostgreSQL: {:?}",
+                        e
+                    )))
+                })?;
+
+        spawn_connection_driver(runtime, connection, notifications);
+        client
+    } else {
+        let connector = tls::build_connector(sslmode, sslrootcert, sslcert, sslkey)?;
+
+        let (client, connection) = connect_with_retry(config, connector, max_retries, retry_backoff)
+            .await
+            .map_err(|e| {
+                PyErr::from(OxpgError::ConnectionFailed(format!(
+                    "Failed to connect to PostgreSQL over TLS: {:?}",
+                    e
+                )))
+            })?;
+
+        spawn_connection_driver(runtime, connection, notifications);
+        client
+    };
+
+    Ok(Arc::new(client))
+}
+
+/// Whether `client`'s server satisfies `target`, per libpq's
+/// `target_session_attrs` semantics: `any` always matches, `read-write`
+/// and `read-only` are decided by asking the server whether the current
+/// transaction is read-only (which, outside an explicit transaction,
+/// reflects the server's `default_transaction_read_only`/recovery state).
+async fn matches_target_session_attrs(
+    client: &PgClient,
+    target: TargetSessionAttrs,
+) -> Result<bool, tokio_postgres::Error> {
+    if target == TargetSessionAttrs::Any {
+        return Ok(true);
+    }
+
+    let rows = client.simple_query("SHOW transaction_read_only").await?;
+    let read_only = rows.iter().any(|message| {
+        matches!(
+            message,
+            tokio_postgres::SimpleQueryMessage::Row(row) if row.get(0) == Some("on")
+        )
+    });
+
+    Ok(match target {
+        TargetSessionAttrs::ReadWrite => !read_only,
+        TargetSessionAttrs::ReadOnly => read_only,
+        TargetSessionAttrs::Any => unreachable!(),
+    })
+}
+
+/// Attempts each of `candidates` in order, accepting the first one that
+/// connects and satisfies `target`. Candidates that fail to connect, or
+/// connect but don't satisfy `target`, are skipped; if none pan out, all
+/// of their failures are reported together.
+#[allow(clippy::too_many_arguments)]
+async fn connect_to_first_matching_host(
+    candidates: &[(String, u16)],
+    base_config: &Config,
+    target: TargetSessionAttrs,
+    sslmode: SslMode,
+    sslrootcert: Option<&str>,
+    sslcert: Option<&str>,
+    sslkey: Option<&str>,
+    runtime: &tokio::runtime::Handle,
+    max_retries: u32,
+    retry_backoff: f64,
+    notifications: Option<NotificationBuffer>,
+) -> PyResult<Arc<PgClient>> {
+    let mut failures = Vec::new();
+
+    for (host, port) in candidates {
+        let host_config = config::config_for_host(base_config, host, *port);
+
+        let client = match open_connection(
+            &host_config,
+            sslmode,
+            sslrootcert,
+            sslcert,
+            sslkey,
+            runtime,
+            max_retries,
+            retry_backoff,
+            notifications.clone(),
+        )
+        .await
+        {
+            Ok(client) => client,
+            Err(e) => {
+                failures.push(format!("{}:{}: {}", host, port, e));
+                continue;
+            }
+        };
+
+        match matches_target_session_attrs(&client, target).await {
+            Ok(true) => return Ok(client),
+            Ok(false) => {
+                failures.push(format!(
+                    "{}:{}: does not satisfy target_session_attrs",
+                    host, port
+                ));
+            }
+            Err(e) => {
+                failures.push(format!("{}:{}: {}", host, port, e));
+            }
+        }
+    }
+
+    Err(OxpgError::ConnectionFailed(format!(
+        "No suitable host found among {} candidate(s): {}",
+        candidates.len(),
+        failures.join("; ")
+    ))
+    .into())
+}
+
+/// Everything `Client` needs to rebuild its connection after a `08`-class
+/// (connection exception) failure, mirroring the parameters
+/// `connect_to_first_matching_host` took when the connection was first
+/// opened so a reconnect attempts the same failover candidates the same way.
+#[derive(Debug, Clone)]
+struct ReconnectState {
+    candidates: Vec<(String, u16)>,
+    base_config: Config,
+    target: TargetSessionAttrs,
+    sslmode: SslMode,
+    sslrootcert: Option<String>,
+    sslcert: Option<String>,
+    sslkey: Option<String>,
+    max_retries: u32,
+    retry_backoff: f64,
+    notifications: NotificationBuffer,
+    reconnect_count: Arc<std::sync::atomic::AtomicU64>,
+}
+
+/// Distinguishes a connection-level failure -- worth transparently
+/// reconnecting and retrying -- from any other error surfacing mid-attempt
+/// (e.g. a parameter conversion error), which should propagate immediately
+/// instead of triggering a reconnect.
+enum QueryAttemptError {
+    Connection(tokio_postgres::Error),
+    Other(PyErr),
+}
+
+impl From<tokio_postgres::Error> for QueryAttemptError {
+    fn from(err: tokio_postgres::Error) -> Self {
+        QueryAttemptError::Connection(err)
+    }
+}
+
+impl From<PyErr> for QueryAttemptError {
+    fn from(err: PyErr) -> Self {
+        QueryAttemptError::Other(err)
+    }
+}
+
+/// Whether `err` indicates `client`'s underlying connection is no longer
+/// usable and worth reconnecting over: a `08`-class SQLSTATE (connection
+/// exception) reported by the server, or the driver itself reporting the
+/// client closed (the connection task died without the server getting to
+/// respond at all, e.g. a network blip).
+fn is_connection_exception(client: &PgClient, err: &tokio_postgres::Error) -> bool {
+    client.is_closed() || err.code().is_some_and(|code| code.code().starts_with("08"))
+}
+
+/// Runs `op` against the current client in `client_slot`, and on a
+/// connection-exception error transparently reconnects -- clearing
+/// `statement_cache` (cached statement IDs are only valid on the connection
+/// that prepared them) and redialing via `connect_to_first_matching_host` --
+/// then retries, up to `reconnect.max_retries` times with the same
+/// exponential backoff `connect` itself uses, capped at ~2s. Safe for
+/// `prepare` and `query`, since re-running a `SELECT` (or re-preparing
+/// statement text that was never sent) has no side effects the caller could
+/// observe twice. `execute`'s `op` is not fully covered by this guarantee --
+/// once its statement has actually been sent to the server, it reports a
+/// connection-exception error through `QueryAttemptError::Other` instead of
+/// `Connection`, so a write already committed server-side is never silently
+/// resubmitted.
+async fn with_reconnect<T, F, Fut>(
+    client_slot: &Arc<Mutex<Arc<PgClient>>>,
+    statement_cache: &StatementCache,
+    reconnect: &ReconnectState,
+    runtime: &tokio::runtime::Handle,
+    mut op: F,
+) -> PyResult<T>
+where
+    F: FnMut(Arc<PgClient>) -> Fut,
+    Fut: std::future::Future<Output = Result<T, QueryAttemptError>>,
+{
+    let mut attempt = 0u32;
+    loop {
+        let client = client_slot.lock().unwrap().clone();
+        match op(client.clone()).await {
+            Ok(value) => return Ok(value),
+            Err(QueryAttemptError::Other(e)) => return Err(e),
+            Err(QueryAttemptError::Connection(err)) => {
+                if attempt >= reconnect.max_retries || !is_connection_exception(&client, &err) {
+                    return Err(PyErr::from(OxpgError::from_db_error(&err)));
+                }
+
+                let delay = (reconnect.retry_backoff * 2f64.powi(attempt as i32)).min(2.0);
+                tokio::time::sleep(std::time::Duration::from_secs_f64(delay)).await;
+
+                statement_cache.lock().unwrap().clear();
+                let fresh = connect_to_first_matching_host(
+                    &reconnect.candidates,
+                    &reconnect.base_config,
+                    reconnect.target,
+                    reconnect.sslmode,
+                    reconnect.sslrootcert.as_deref(),
+                    reconnect.sslcert.as_deref(),
+                    reconnect.sslkey.as_deref(),
+                    runtime,
+                    reconnect.max_retries,
+                    reconnect.retry_backoff,
+                    Some(reconnect.notifications.clone()),
+                )
+                .await?;
+                *client_slot.lock().unwrap() = fresh;
+                reconnect
+                    .reconnect_count
+                    .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                attempt += 1;
+            }
+        }
+    }
+}
 
 #[gen_stub_pyclass]
 #[pyclass]
@@ -24,13 +412,83 @@ pub struct Client {
     port: u16,
     db: String,
     user: String,
-    client: Arc<PgClient>,
+    client: Arc<Mutex<Arc<PgClient>>>,
     runtime: tokio::runtime::Runtime,
+    statement_cache: StatementCache,
+    type_decoders: TypeHandlers,
+    type_encoders: TypeHandlers,
+    type_encoders_by_name: TypeHandlersByName,
+    native_types: bool,
+    notifications: NotificationBuffer,
+    reconnect: ReconnectState,
 }
 
 #[gen_stub_pymethods]
 #[pymethods]
 impl Client {
+    /// Starts listening on `channel`, so `NOTIFY`s sent to it show up via
+    /// `notifications()`.
+    fn listen<'a>(&'a self, py: Python<'a>, channel: String) -> PyResult<Bound<'a, PyAny>> {
+        let client = self.client.lock().unwrap().clone();
+        future_into_py(py, async move {
+            client
+                .batch_execute(&format!("LISTEN {}", notify::quote_identifier(&channel)))
+                .await
+                .map_err(|e| PyErr::from(OxpgError::from_db_error(&e)))
+        })
+    }
+
+    /// Stops listening on `channel`.
+    fn unlisten<'a>(&'a self, py: Python<'a>, channel: String) -> PyResult<Bound<'a, PyAny>> {
+        let client = self.client.lock().unwrap().clone();
+        future_into_py(py, async move {
+            client
+                .batch_execute(&format!("UNLISTEN {}", notify::quote_identifier(&channel)))
+                .await
+                .map_err(|e| PyErr::from(OxpgError::from_db_error(&e)))
+        })
+    }
+
+    /// Returns a blocking iterator over buffered `Notification`s. If
+    /// `timeout` is given, `__next__` ends the iteration once that many
+    /// seconds pass without a new notification; otherwise it blocks
+    /// indefinitely. Call `.poll()` on the returned object instead to drain
+    /// whatever is already buffered without blocking at all.
+    #[pyo3(signature = (timeout=None))]
+    fn notifications(&self, timeout: Option<f64>) -> NotificationStream {
+        NotificationStream::new(self.notifications.clone(), timeout)
+    }
+
+    /// Begins a transaction, returning a `Transaction` usable standalone
+    /// (`commit()`/`rollback()`) or as an `async with` block. `isolation_level`
+    /// is one of `"read committed"`, `"repeatable read"`, `"serializable"`;
+    /// `read_only`/`deferrable` are emitted as `BEGIN` options when given.
+    #[pyo3(signature = (isolation_level=None, read_only=None, deferrable=None))]
+    fn transaction<'a>(
+        &'a self,
+        py: Python<'a>,
+        isolation_level: Option<String>,
+        read_only: Option<bool>,
+        deferrable: Option<bool>,
+    ) -> PyResult<Bound<'a, PyAny>> {
+        let client = self.client.lock().unwrap().clone();
+        let statement_cache = self.statement_cache.clone();
+        let type_decoders = self.type_decoders.clone();
+        let type_encoders = self.type_encoders.clone();
+        let type_encoders_by_name = self.type_encoders_by_name.clone();
+        let native_types = self.native_types;
+        future_into_py(py, async move {
+            transaction::begin(&client, isolation_level.as_deref(), read_only, deferrable).await?;
+            Ok(Transaction::new_top_level(
+                client,
+                statement_cache,
+                type_decoders,
+                type_encoders,
+                type_encoders_by_name,
+                native_types,
+            ))
+        })
+    }
     #[pyo3(signature = (query, *args))]
     fn query<'a>(
         &'a self,
@@ -38,32 +496,57 @@ impl Client {
         query: String,
         args: &Bound<'a, PyTuple>,
     ) -> PyResult<Bound<'a, PyAny>> {
-        let client = self.client.clone();
-        let mut owned_params = extract_params(args)?;
+        let client_slot = self.client.clone();
+        let statement_cache = self.statement_cache.clone();
+        let type_decoders = self.type_decoders.clone();
+        let type_encoders = self.type_encoders.clone();
+        let type_encoders_by_name = self.type_encoders_by_name.clone();
+        let native_types = self.native_types;
+        let reconnect = self.reconnect.clone();
+        let runtime = self.runtime.handle().clone();
+        let owned_params_template = extract_params(args)?;
         let query = query.clone();
         future_into_py(py, async move {
-            let statement = client.prepare(&query).await.map_err(|e| {
-                PyErr::from(OxpgError::ExecutionError(format!(
-                    "Error while generating statement: {:?}",
-                    e
-                )))
-            })?;
+            let rows = with_reconnect(
+                &client_slot,
+                &statement_cache,
+                &reconnect,
+                &runtime,
+                |client| {
+                    let statement_cache = statement_cache.clone();
+                    let type_encoders = type_encoders.clone();
+                    let type_encoders_by_name = type_encoders_by_name.clone();
+                    let query = query.clone();
+                    let mut owned_params = owned_params_template.clone();
+                    async move {
+                        let statement = prepare_cached(&client, &statement_cache, &query).await?;
 
-            refine_params(&mut owned_params, &statement);
-            let ref_params: Vec<&(dyn ToSql + Sync)> =
-                owned_params.iter().map(|p| p.as_ref()).collect();
+                        Python::attach(|py| {
+                            let encoders = type_encoders.lock().unwrap();
+                            let encoders_by_name = type_encoders_by_name.lock().unwrap();
+                            refine_params(
+                                py,
+                                &mut owned_params,
+                                &statement,
+                                &encoders,
+                                &encoders_by_name,
+                            )
+                        })?;
+                        let ref_params: Vec<&(dyn ToSql + Sync)> =
+                            owned_params.iter().map(|p| p.as_ref()).collect();
 
-            let rows = client.query(&statement, &ref_params).await.map_err(|e| {
-                PyErr::from(OxpgError::ExecutionError(format!(
-                    "Error while executing query: {:?}",
-                    e
-                )))
-            })?;
+                        let rows = client.query(statement.as_ref(), &ref_params).await?;
+                        Ok(rows)
+                    }
+                },
+            )
+            .await?;
 
             Python::attach(|py| -> PyResult<Py<PyAny>> {
+                let decoders = type_decoders.lock().unwrap();
                 let result = PyList::empty(py);
                 for row in rows {
-                    let py_row = conversions::row_to_dict(py, &row)?;
+                    let py_row = conversions::row_to_dict(py, &row, &decoders, native_types)?;
                     result.append(py_row).map_err(|e| {
                         PyErr::from(OxpgError::DataConversionError(format!(
                             "Failed to append row to result list: {:?}",
@@ -83,32 +566,234 @@ impl Client {
         query: String,
         args: &Bound<'a, PyTuple>,
     ) -> PyResult<Bound<'a, PyAny>> {
-        let client = self.client.clone();
-        let mut owned_params = extract_params(args)?;
+        let client_slot = self.client.clone();
+        let statement_cache = self.statement_cache.clone();
+        let type_encoders = self.type_encoders.clone();
+        let type_encoders_by_name = self.type_encoders_by_name.clone();
+        let reconnect = self.reconnect.clone();
+        let runtime = self.runtime.handle().clone();
+        let owned_params_template = extract_params(args)?;
         let query = query.clone();
         future_into_py(py, async move {
-            let statement = client.prepare(&query).await.map_err(|e| {
-                PyErr::from(OxpgError::ExecutionError(format!(
-                    "Error while generating statement: {:?}",
-                    e
-                )))
-            })?;
+            let result = with_reconnect(
+                &client_slot,
+                &statement_cache,
+                &reconnect,
+                &runtime,
+                |client| {
+                    let statement_cache = statement_cache.clone();
+                    let type_encoders = type_encoders.clone();
+                    let type_encoders_by_name = type_encoders_by_name.clone();
+                    let query = query.clone();
+                    let mut owned_params = owned_params_template.clone();
+                    async move {
+                        let statement = prepare_cached(&client, &statement_cache, &query).await?;
 
-            refine_params(&mut owned_params, &statement);
-            let ref_params: Vec<&(dyn ToSql + Sync)> =
-                owned_params.iter().map(|p| p.as_ref()).collect();
+                        Python::attach(|py| {
+                            let encoders = type_encoders.lock().unwrap();
+                            let encoders_by_name = type_encoders_by_name.lock().unwrap();
+                            refine_params(
+                                py,
+                                &mut owned_params,
+                                &statement,
+                                &encoders,
+                                &encoders_by_name,
+                            )
+                        })?;
+                        let ref_params: Vec<&(dyn ToSql + Sync)> =
+                            owned_params.iter().map(|p| p.as_ref()).collect();
 
-            let result = client.execute(&statement, &ref_params).await.map_err(|e| {
-                PyErr::from(OxpgError::ExecutionError(format!(
-                    "Error while executing query: {:?}",
-                    e
-                )))
-            })?;
+                        // Once the statement text has actually been sent to
+                        // the server, a connection-exception error no longer
+                        // means the write didn't happen -- it only means we
+                        // didn't observe whether it did. Reconnecting and
+                        // resubmitting here could double-apply an INSERT/
+                        // UPDATE/DELETE the server already committed, so this
+                        // error is reported as-is instead of going through
+                        // `QueryAttemptError::Connection`'s retry path.
+                        let result = client.execute(statement.as_ref(), &ref_params).await.map_err(
+                            |e| QueryAttemptError::Other(PyErr::from(OxpgError::from_db_error(&e))),
+                        )?;
+                        Ok(result)
+                    }
+                },
+            )
+            .await?;
 
             Ok(result)
         })
     }
 
+    /// Prepares `query` once, then runs it once per tuple in `params_seq`
+    /// as a pipelined set of futures -- every execution is in flight at the
+    /// same time rather than awaited one at a time -- and returns the
+    /// summed affected-row count.
+    #[pyo3(signature = (query, params_seq))]
+    fn execute_many<'a>(
+        &'a self,
+        py: Python<'a>,
+        query: String,
+        params_seq: &Bound<'a, PyAny>,
+    ) -> PyResult<Bound<'a, PyAny>> {
+        let client = self.client.lock().unwrap().clone();
+        let statement_cache = self.statement_cache.clone();
+        let type_encoders = self.type_encoders.clone();
+        let type_encoders_by_name = self.type_encoders_by_name.clone();
+
+        let mut owned_params_seq = Vec::new();
+        for params in params_seq.try_iter()? {
+            let params = params?;
+            let tuple = params.downcast::<PyTuple>().map_err(|_| {
+                PyErr::from(OxpgError::InvalidParameter(
+                    "execute_many expects a sequence of parameter tuples".to_string(),
+                ))
+            })?;
+            owned_params_seq.push(extract_params(tuple)?);
+        }
+
+        let query = query.clone();
+        future_into_py(py, async move {
+            let statement = prepare_cached(&client, &statement_cache, &query)
+                .await
+                .map_err(|e| PyErr::from(OxpgError::from_db_error(&e)))?;
+
+            let mut set = tokio::task::JoinSet::new();
+            for mut owned_params in owned_params_seq {
+                let client = client.clone();
+                let statement = statement.clone();
+                let type_encoders = type_encoders.clone();
+                let type_encoders_by_name = type_encoders_by_name.clone();
+                set.spawn(async move {
+                    Python::attach(|py| {
+                        let encoders = type_encoders.lock().unwrap();
+                        let encoders_by_name = type_encoders_by_name.lock().unwrap();
+                        refine_params(py, &mut owned_params, &statement, &encoders, &encoders_by_name)
+                    })?;
+                    let ref_params: Vec<&(dyn ToSql + Sync)> =
+                        owned_params.iter().map(|p| p.as_ref()).collect();
+
+                    client
+                        .execute(statement.as_ref(), &ref_params)
+                        .await
+                        .map_err(|e| PyErr::from(OxpgError::from_db_error(&e)))
+                });
+            }
+
+            let mut total = 0u64;
+            while let Some(result) = set.join_next().await {
+                let affected = result.map_err(|e| {
+                    PyErr::from(OxpgError::Unexpected(format!(
+                        "execute_many task panicked: {}",
+                        e
+                    )))
+                })??;
+                total += affected;
+            }
+
+            Ok(total)
+        })
+    }
+
+    /// Prepares `query` once and returns a reusable `PreparedStatement`
+    /// handle whose own `query`/`execute` skip the cache lookup on every
+    /// call. Equivalent to calling `Client.query`/`Client.execute` with the
+    /// same SQL repeatedly, just without re-hashing the query text each time.
+    fn prepare<'a>(&'a self, py: Python<'a>, query: String) -> PyResult<Bound<'a, PyAny>> {
+        let client_slot = self.client.clone();
+        let statement_cache = self.statement_cache.clone();
+        let type_decoders = self.type_decoders.clone();
+        let type_encoders = self.type_encoders.clone();
+        let type_encoders_by_name = self.type_encoders_by_name.clone();
+        let native_types = self.native_types;
+        let reconnect = self.reconnect.clone();
+        let runtime = self.runtime.handle().clone();
+        future_into_py(py, async move {
+            let (client, statement) = with_reconnect(
+                &client_slot,
+                &statement_cache,
+                &reconnect,
+                &runtime,
+                |client| {
+                    let statement_cache = statement_cache.clone();
+                    let query = query.clone();
+                    async move {
+                        let statement = prepare_cached(&client, &statement_cache, &query).await?;
+                        Ok((client, statement))
+                    }
+                },
+            )
+            .await?;
+
+            Ok(PreparedStatement {
+                client,
+                statement,
+                type_decoders,
+                type_encoders,
+                type_encoders_by_name,
+                native_types,
+            })
+        })
+    }
+
+    /// Evicts `query`'s cached prepared statement, if any. Has no effect on
+    /// `PreparedStatement` handles already obtained via `prepare`.
+    fn deallocate(&self, query: String) {
+        self.statement_cache.lock().unwrap().pop(&query);
+    }
+
+    /// Clears the prepared-statement cache entirely.
+    fn clear_cache(&self) {
+        self.statement_cache.lock().unwrap().clear();
+    }
+
+    /// Registers a Python callable `bytes -> object` invoked whenever `query`
+    /// encounters a column whose Postgres type OID it doesn't natively
+    /// decode (enums, composites, `inet`, `ltree`, domains, ...).
+    fn register_type(&self, oid: u32, decoder: Py<PyAny>) {
+        self.type_decoders.lock().unwrap().insert(oid, decoder);
+    }
+
+    /// Registers the reverse of `register_type`: a Python callable
+    /// `object -> bytes | str` used to encode a query parameter destined for
+    /// the given Postgres type OID.
+    fn register_type_encoder(&self, oid: u32, encoder: Py<PyAny>) {
+        self.type_encoders.lock().unwrap().insert(oid, encoder);
+    }
+
+    /// Like `register_type_encoder`, but keyed by the Python type's own name
+    /// instead of a target OID. Consulted whenever a parameter's OID has no
+    /// registered encoder, letting a custom Python type encode itself the
+    /// same way regardless of which column it ends up bound to.
+    fn register_type_encoder_for_type(&self, type_name: String, encoder: Py<PyAny>) {
+        self.type_encoders_by_name
+            .lock()
+            .unwrap()
+            .insert(type_name, encoder);
+    }
+
+    /// How many times `query`/`execute`/`prepare` has transparently
+    /// reconnected after a connection-exception error.
+    fn reconnect_count(&self) -> u64 {
+        self.reconnect
+            .reconnect_count
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Captures a `CancelHandle` that can later ask the server to abort
+    /// whatever statement is running on this connection, independent of
+    /// whether `query`/`execute` ever returns -- useful for wiring Postgres
+    /// statement cancellation to a Python signal handler or a timeout.
+    fn cancel_token(&self) -> CancelHandle {
+        let client = self.client.lock().unwrap().clone();
+        CancelHandle {
+            token: client.cancel_token(),
+            sslmode: self.reconnect.sslmode,
+            sslrootcert: self.reconnect.sslrootcert.clone(),
+            sslcert: self.reconnect.sslcert.clone(),
+            sslkey: self.reconnect.sslkey.clone(),
+        }
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "Client(host='{}', port={}, db='{}', user='{}')",
@@ -117,9 +802,144 @@ impl Client {
     }
 }
 
+/// A lightweight handle, obtained via `Client.cancel_token`, that can ask the
+/// server to abort an in-flight statement on the connection it was captured
+/// from. Mirrors the `CancelToken`-plus-TLS-settings pattern connection
+/// poolers use to wrap a cancel closure alongside a pooled connection.
+#[gen_stub_pyclass]
+#[pyclass]
+pub struct CancelHandle {
+    token: tokio_postgres::CancelToken,
+    sslmode: SslMode,
+    sslrootcert: Option<String>,
+    sslcert: Option<String>,
+    sslkey: Option<String>,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl CancelHandle {
+    /// Asks the server to abort whatever statement is currently running on
+    /// the connection this handle was captured from. This is
+    /// fire-and-forget: the in-flight `query`/`execute` call itself reports
+    /// its own cancellation back to the caller awaiting it, so succeeding
+    /// here just means the cancel request reached the server.
+    fn cancel<'a>(&'a self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let token = self.token.clone();
+        let sslmode = self.sslmode;
+        let sslrootcert = self.sslrootcert.clone();
+        let sslcert = self.sslcert.clone();
+        let sslkey = self.sslkey.clone();
+        future_into_py(py, async move {
+            let result = if sslmode == SslMode::Disable {
+                token.cancel_query(tokio_postgres::NoTls).await
+            } else {
+                let connector = tls::build_connector(
+                    sslmode,
+                    sslrootcert.as_deref(),
+                    sslcert.as_deref(),
+                    sslkey.as_deref(),
+                )?;
+                token.cancel_query(connector).await
+            };
+
+            result.map_err(|e| {
+                PyErr::from(OxpgError::ConnectionFailed(format!(
+                    "Failed to deliver cancel request: {:?}",
+                    e
+                )))
+            })
+        })
+    }
+}
+
+/// A statement prepared ahead of time via `Client.prepare`. Reuses the same
+/// cached `Statement` on every `query`/`execute` call instead of looking it
+/// up by SQL text each time.
+#[gen_stub_pyclass]
+#[pyclass]
+pub struct PreparedStatement {
+    client: Arc<PgClient>,
+    statement: Arc<Statement>,
+    type_decoders: TypeHandlers,
+    type_encoders: TypeHandlers,
+    type_encoders_by_name: TypeHandlersByName,
+    native_types: bool,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PreparedStatement {
+    #[pyo3(signature = (*args))]
+    fn query<'a>(&'a self, py: Python<'a>, args: &Bound<'a, PyTuple>) -> PyResult<Bound<'a, PyAny>> {
+        let client = self.client.clone();
+        let statement = self.statement.clone();
+        let type_decoders = self.type_decoders.clone();
+        let type_encoders = self.type_encoders.clone();
+        let type_encoders_by_name = self.type_encoders_by_name.clone();
+        let native_types = self.native_types;
+        let mut owned_params = extract_params(args)?;
+        future_into_py(py, async move {
+            Python::attach(|py| {
+                let encoders = type_encoders.lock().unwrap();
+                let encoders_by_name = type_encoders_by_name.lock().unwrap();
+                refine_params(py, &mut owned_params, &statement, &encoders, &encoders_by_name)
+            })?;
+            let ref_params: Vec<&(dyn ToSql + Sync)> =
+                owned_params.iter().map(|p| p.as_ref()).collect();
+
+            let rows = client
+                .query(statement.as_ref(), &ref_params)
+                .await
+                .map_err(|e| PyErr::from(OxpgError::from_db_error(&e)))?;
+
+            Python::attach(|py| -> PyResult<Py<PyAny>> {
+                let decoders = type_decoders.lock().unwrap();
+                let result = PyList::empty(py);
+                for row in rows {
+                    let py_row = conversions::row_to_dict(py, &row, &decoders, native_types)?;
+                    result.append(py_row).map_err(|e| {
+                        PyErr::from(OxpgError::DataConversionError(format!(
+                            "Failed to append row to result list: {:?}",
+                            e
+                        )))
+                    })?;
+                }
+                Ok(result.into_any().unbind())
+            })
+        })
+    }
+
+    #[pyo3(signature = (*args))]
+    fn execute<'a>(&'a self, py: Python<'a>, args: &Bound<'a, PyTuple>) -> PyResult<Bound<'a, PyAny>> {
+        let client = self.client.clone();
+        let statement = self.statement.clone();
+        let type_encoders = self.type_encoders.clone();
+        let type_encoders_by_name = self.type_encoders_by_name.clone();
+        let mut owned_params = extract_params(args)?;
+        future_into_py(py, async move {
+            Python::attach(|py| {
+                let encoders = type_encoders.lock().unwrap();
+                let encoders_by_name = type_encoders_by_name.lock().unwrap();
+                refine_params(py, &mut owned_params, &statement, &encoders, &encoders_by_name)
+            })?;
+            let ref_params: Vec<&(dyn ToSql + Sync)> =
+                owned_params.iter().map(|p| p.as_ref()).collect();
+
+            let result = client
+                .execute(statement.as_ref(), &ref_params)
+                .await
+                .map_err(|e| PyErr::from(OxpgError::from_db_error(&e)))?;
+
+            Ok(result)
+        })
+    }
+}
+
 #[gen_stub_pyfunction]
 #[pyfunction]
-#[pyo3(signature = (dsn=None, host=None, user=None, password=None, port=5432, db="postgres".to_string()))]
+#[pyo3(signature = (dsn=None, host=None, user=None, password=None, port=5432, db="postgres".to_string(), sslmode=None, sslrootcert=None, sslcert=None, sslkey=None, statement_cache_size=DEFAULT_STATEMENT_CACHE_SIZE, native_types=true, max_retries=3, retry_backoff=0.1, target_session_attrs=None))]
+#[allow(clippy::too_many_arguments)]
 pub fn connect(
     py: Python<'_>,
     dsn: Option<String>,
@@ -128,13 +948,38 @@ pub fn connect(
     password: Option<String>,
     port: u16,
     db: String,
+    sslmode: Option<String>,
+    sslrootcert: Option<String>,
+    sslcert: Option<String>,
+    sslkey: Option<String>,
+    statement_cache_size: usize,
+    native_types: bool,
+    max_retries: u32,
+    retry_backoff: f64,
+    target_session_attrs: Option<String>,
 ) -> PyResult<Client> {
     validate_connect_params(&dsn, &host, &user, &password)?;
 
+    let statement_cache_capacity = NonZeroUsize::new(statement_cache_size).ok_or_else(|| {
+        OxpgError::InvalidParameter("statement_cache_size must be greater than zero".to_string())
+    })?;
+
+    let target_session_attrs = TargetSessionAttrs::parse(target_session_attrs.as_deref())?;
+
     let mut config = Config::new();
 
-    let (host, user, port, db, config) = match dsn {
-        Some(s) => config::extract_host_from_dsn(s, &mut config)?,
+    // `host`/`port` below are kept for `Client`'s `host`/`port`/`__repr__`
+    // fields: a DSN's first candidate, or the `host`/`port` keyword
+    // arguments exactly as passed. `candidates` carries the full failover
+    // list (every `host=` in a multi-host DSN, or every entry of a
+    // comma-separated `host` keyword such as `h1:5432,h2:5433`).
+    let (host, user, port, db, config, candidates, raw_sslmode) = match dsn {
+        Some(s) => {
+            let raw_sslmode = config::raw_sslmode_from_dsn(&s);
+            let (host, user, port, db, config) = config::extract_host_from_dsn(s, &mut config)?;
+            let candidates = config::host_candidates(config);
+            (host, user, port, db, config, candidates, raw_sslmode)
+        }
         None => {
             let host = host.ok_or_else(|| OxpgError::MissingParameter("host".to_string()))?;
             let user = user.ok_or_else(|| OxpgError::MissingParameter("user".to_string()))?;
@@ -149,10 +994,16 @@ pub fn connect(
                 db.clone(),
                 &mut config,
             );
-            (host, user, port, db, config)
+            let candidates = config::host_candidates(config);
+            (host, user, port, db, config, candidates, None)
         }
     };
 
+    let sslmode = match sslmode.or(raw_sslmode) {
+        Some(s) => SslMode::parse(Some(&s))?,
+        None => SslMode::from_config_ssl_mode(config.get_ssl_mode()),
+    };
+
     let runtime = tokio::runtime::Runtime::new().map_err(|e| {
         PyErr::from(OxpgError::RuntimeFailed(format!(
             "Failed to create Tokio runtime: {:?}",
@@ -160,28 +1011,52 @@ pub fn connect(
         )))
     })?;
 
-    let (client, connection) = py
-        .detach(|| runtime.block_on(async { config.connect(tokio_postgres::NoTls).await }))
-        .map_err(|e| {
-            PyErr::from(OxpgError::ConnectionFailed(format!(
-                "Failed to connect to PostgreSQL: {:?}",
-                e
-            )))
-        })?;
+    let handle = runtime.handle().clone();
+    let base_config = config.clone();
+    let notifications: NotificationBuffer = Arc::new(Mutex::new(VecDeque::new()));
+    let client = py.detach(|| {
+        runtime.block_on(connect_to_first_matching_host(
+            &candidates,
+            &base_config,
+            target_session_attrs,
+            sslmode,
+            sslrootcert.as_deref(),
+            sslcert.as_deref(),
+            sslkey.as_deref(),
+            &handle,
+            max_retries,
+            retry_backoff,
+            Some(notifications.clone()),
+        ))
+    })?;
 
-    runtime.spawn(async move {
-        if let Err(e) = connection.await {
-            eprintln!("Connection error: {}", e);
-        }
-    });
-    let client = Arc::new(client);
+    let reconnect = ReconnectState {
+        candidates,
+        base_config,
+        target: target_session_attrs,
+        sslmode,
+        sslrootcert,
+        sslcert,
+        sslkey,
+        max_retries,
+        retry_backoff,
+        notifications: notifications.clone(),
+        reconnect_count: Arc::new(std::sync::atomic::AtomicU64::new(0)),
+    };
 
     Ok(Client {
         host,
         port,
         db,
         user,
-        client,
+        client: Arc::new(Mutex::new(client)),
         runtime,
+        statement_cache: Arc::new(Mutex::new(LruCache::new(statement_cache_capacity))),
+        type_decoders: Arc::new(Mutex::new(HashMap::new())),
+        type_encoders: Arc::new(Mutex::new(HashMap::new())),
+        type_encoders_by_name: Arc::new(Mutex::new(HashMap::new())),
+        native_types,
+        notifications,
+        reconnect,
     })
 }
@@ -0,0 +1,108 @@
+//! LISTEN/NOTIFY support: notifications arriving on the connection's
+//! background I/O driver are buffered here so they can be drained by a
+//! blocking Python iterator without losing anything received between
+//! queries.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use pyo3::exceptions::PyStopIteration;
+use pyo3::prelude::*;
+use pyo3_stub_gen::derive::*;
+
+pub(crate) type NotificationBuffer = Arc<Mutex<VecDeque<Notification>>>;
+
+/// A single `NOTIFY` received on a channel this session is `LISTEN`ing on.
+#[gen_stub_pyclass]
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct Notification {
+    #[pyo3(get)]
+    channel: String,
+    #[pyo3(get)]
+    pid: i32,
+    #[pyo3(get)]
+    payload: String,
+}
+
+impl Notification {
+    pub(crate) fn from_pg(n: &tokio_postgres::Notification) -> Notification {
+        Notification {
+            channel: n.channel().to_string(),
+            pid: n.process_id(),
+            payload: n.payload().to_string(),
+        }
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl Notification {
+    fn __repr__(&self) -> String {
+        format!(
+            "Notification(channel={:?}, pid={}, payload={:?})",
+            self.channel, self.pid, self.payload
+        )
+    }
+}
+
+/// A blocking iterator over `Notification`s buffered for a `Client`,
+/// returned by `Client.notifications()`. `__next__` blocks (without
+/// holding the GIL) until a notification arrives or `timeout` elapses, at
+/// which point it ends the iteration; `poll()` drains whatever is already
+/// buffered without waiting at all.
+#[gen_stub_pyclass]
+#[pyclass]
+pub struct NotificationStream {
+    buffer: NotificationBuffer,
+    timeout: Option<f64>,
+}
+
+impl NotificationStream {
+    pub(crate) fn new(buffer: NotificationBuffer, timeout: Option<f64>) -> NotificationStream {
+        NotificationStream { buffer, timeout }
+    }
+}
+
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl NotificationStream {
+    /// Drains and returns every notification buffered so far, without
+    /// blocking even if the buffer is currently empty.
+    fn poll(&self) -> Vec<Notification> {
+        self.buffer.lock().unwrap().drain(..).collect()
+    }
+
+    fn __iter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __next__(&self, py: Python<'_>) -> PyResult<Notification> {
+        let buffer = self.buffer.clone();
+        let deadline = self
+            .timeout
+            .map(|secs| Instant::now() + Duration::from_secs_f64(secs));
+
+        py.detach(|| loop {
+            if let Some(notification) = buffer.lock().unwrap().pop_front() {
+                return Ok(notification);
+            }
+
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Err(PyStopIteration::new_err(()));
+            }
+
+            std::thread::sleep(POLL_INTERVAL);
+        })
+    }
+}
+
+/// Quotes `ident` as a Postgres identifier (doubling embedded `"`s), since
+/// `LISTEN`/`UNLISTEN` take a bare channel name rather than a bindable
+/// parameter.
+pub(crate) fn quote_identifier(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
@@ -0,0 +1,655 @@
+//! A bounded pool of live connections, handed out via `acquire()` and
+//! returned to the pool when the caller releases them (explicitly, or via
+//! `with pool.acquire() as conn: ...`), or used directly through `Pool`'s
+//! own `query`/`execute`, which check a connection out and back in around a
+//! single call.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::client::config::{
+    populate_config_from_params, raw_sslmode_from_dsn, validate_connect_params,
+    extract_host_from_dsn,
+};
+use crate::client::conversions::{extract_params, refine_params, row_to_dict, OwnedParam};
+use crate::client::tls::SslMode;
+use crate::client::{open_connection, TypeHandlers, TypeHandlersByName};
+use crate::errors::OxpgError;
+use pyo3::prelude::*;
+use pyo3::types::{PyList, PyTuple};
+use pyo3_async_runtimes::tokio::future_into_py;
+use pyo3_stub_gen::derive::*;
+use tokio_postgres::types::ToSql;
+use tokio_postgres::{Client as PgClient, Config};
+
+struct IdleConn {
+    client: Arc<PgClient>,
+    idle_since: Instant,
+}
+
+#[gen_stub_pyclass]
+#[pyclass]
+pub struct Pool {
+    config: Config,
+    sslmode: SslMode,
+    sslrootcert: Option<String>,
+    sslcert: Option<String>,
+    sslkey: Option<String>,
+    runtime: Arc<tokio::runtime::Runtime>,
+    idle: Arc<Mutex<VecDeque<IdleConn>>>,
+    semaphore: Arc<tokio::sync::Semaphore>,
+    max_size: usize,
+    timeout: f64,
+    max_idle: f64,
+}
+
+/// Checks a connection out of `idle`/`semaphore`: waits up to `timeout`
+/// seconds for a free slot, reuses a healthy idle connection if one is
+/// available (health-checked with a cheap `SELECT 1` once it's been idle
+/// past `max_idle` seconds, discarding and trying the next one if that
+/// fails), and otherwise opens a brand new connection from `config`.
+#[allow(clippy::too_many_arguments)]
+async fn checkout(
+    idle: &Arc<Mutex<VecDeque<IdleConn>>>,
+    semaphore: &Arc<tokio::sync::Semaphore>,
+    config: &Config,
+    sslmode: SslMode,
+    sslrootcert: Option<&str>,
+    sslcert: Option<&str>,
+    sslkey: Option<&str>,
+    runtime: &tokio::runtime::Handle,
+    timeout: f64,
+    max_idle: f64,
+) -> PyResult<(Arc<PgClient>, tokio::sync::OwnedSemaphorePermit)> {
+    let permit = tokio::time::timeout(Duration::from_secs_f64(timeout), semaphore.clone().acquire_owned())
+        .await
+        .map_err(|_| {
+            PyErr::from(OxpgError::ConnectionFailed(format!(
+                "Timed out after {}s waiting for a free pooled connection",
+                timeout
+            )))
+        })?
+        .map_err(|e| {
+            PyErr::from(OxpgError::Unexpected(format!(
+                "Connection pool semaphore closed: {}",
+                e
+            )))
+        })?;
+
+    let mut candidate = idle.lock().unwrap().pop_front();
+    let client = loop {
+        match candidate.take() {
+            Some(entry) => {
+                if entry.idle_since.elapsed() < Duration::from_secs_f64(max_idle)
+                    || entry.client.simple_query("SELECT 1").await.is_ok()
+                {
+                    break entry.client;
+                }
+                candidate = idle.lock().unwrap().pop_front();
+            }
+            None => {
+                break open_connection(
+                    config,
+                    sslmode,
+                    sslrootcert,
+                    sslcert,
+                    sslkey,
+                    runtime,
+                    0,
+                    0.0,
+                    None,
+                )
+                .await?;
+            }
+        }
+    };
+
+    Ok((client, permit))
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl Pool {
+    /// Builds a bounded pool of up to `max_size` connections to the same
+    /// server, resolved from either `dsn` or the individual host/user/
+    /// password/port/db parameters, exactly like `connect()`.
+    #[new]
+    #[pyo3(signature = (dsn=None, host=None, user=None, password=None, port=5432, db="postgres".to_string(), sslmode=None, sslrootcert=None, sslcert=None, sslkey=None, min_size=0, max_size=10, max_idle=300.0, timeout=30.0))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        py: Python<'_>,
+        dsn: Option<String>,
+        host: Option<String>,
+        user: Option<String>,
+        password: Option<String>,
+        port: u16,
+        db: String,
+        sslmode: Option<String>,
+        sslrootcert: Option<String>,
+        sslcert: Option<String>,
+        sslkey: Option<String>,
+        min_size: usize,
+        max_size: usize,
+        max_idle: f64,
+        timeout: f64,
+    ) -> PyResult<Pool> {
+        build_pool(
+            py,
+            dsn,
+            host,
+            user,
+            password,
+            port,
+            db,
+            sslmode,
+            sslrootcert,
+            sslcert,
+            sslkey,
+            min_size,
+            max_size,
+            max_idle,
+            timeout,
+        )
+    }
+
+    /// Hands out a pooled connection, reusing a healthy idle one if
+    /// available, opening a new one if the pool hasn't reached `max_size`
+    /// yet, or waiting up to `timeout` seconds for one to free up otherwise.
+    fn acquire<'a>(&'a self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let config = self.config.clone();
+        let sslmode = self.sslmode;
+        let sslrootcert = self.sslrootcert.clone();
+        let sslcert = self.sslcert.clone();
+        let sslkey = self.sslkey.clone();
+        let handle = self.runtime.handle().clone();
+        let idle = self.idle.clone();
+        let semaphore = self.semaphore.clone();
+        let timeout = self.timeout;
+        let max_idle = self.max_idle;
+
+        future_into_py(py, async move {
+            let (client, permit) = checkout(
+                &idle,
+                &semaphore,
+                &config,
+                sslmode,
+                sslrootcert.as_deref(),
+                sslcert.as_deref(),
+                sslkey.as_deref(),
+                &handle,
+                timeout,
+                max_idle,
+            )
+            .await?;
+
+            Ok(PooledConnection {
+                client: Some(client),
+                idle,
+                permit: Some(permit),
+            })
+        })
+    }
+
+    #[pyo3(signature = (query, *args))]
+    fn query<'a>(
+        &'a self,
+        py: Python<'a>,
+        query: String,
+        args: &Bound<'a, PyTuple>,
+    ) -> PyResult<Bound<'a, PyAny>> {
+        let config = self.config.clone();
+        let sslmode = self.sslmode;
+        let sslrootcert = self.sslrootcert.clone();
+        let sslcert = self.sslcert.clone();
+        let sslkey = self.sslkey.clone();
+        let handle = self.runtime.handle().clone();
+        let idle = self.idle.clone();
+        let semaphore = self.semaphore.clone();
+        let timeout = self.timeout;
+        let max_idle = self.max_idle;
+        let mut owned_params = extract_params(args)?;
+
+        future_into_py(py, async move {
+            let (client, permit) = checkout(
+                &idle,
+                &semaphore,
+                &config,
+                sslmode,
+                sslrootcert.as_deref(),
+                sslcert.as_deref(),
+                sslkey.as_deref(),
+                &handle,
+                timeout,
+                max_idle,
+            )
+            .await?;
+
+            let rows = run_query(&client, &query, &mut owned_params).await;
+
+            // A query that failed with a logical error (bad SQL, constraint
+            // violation, ...) leaves the connection itself perfectly healthy
+            // and safe to reuse; only a connection that's actually closed is
+            // worth discarding instead of handing back to the next checkout.
+            if !client.is_closed() {
+                idle.lock().unwrap().push_back(IdleConn {
+                    client,
+                    idle_since: Instant::now(),
+                });
+            }
+            drop(permit);
+
+            let rows = rows?;
+            Python::attach(|py| -> PyResult<Py<PyAny>> {
+                let empty_decoders = HashMap::new();
+                let result = PyList::empty(py);
+                for row in rows {
+                    let py_row = row_to_dict(py, &row, &empty_decoders, true)?;
+                    result.append(py_row).map_err(|e| {
+                        PyErr::from(OxpgError::DataConversionError(format!(
+                            "Failed to append row to result list: {:?}",
+                            e
+                        )))
+                    })?;
+                }
+                Ok(result.into_any().unbind())
+            })
+        })
+    }
+
+    #[pyo3(signature = (query, *args))]
+    fn execute<'a>(
+        &'a self,
+        py: Python<'a>,
+        query: String,
+        args: &Bound<'a, PyTuple>,
+    ) -> PyResult<Bound<'a, PyAny>> {
+        let config = self.config.clone();
+        let sslmode = self.sslmode;
+        let sslrootcert = self.sslrootcert.clone();
+        let sslcert = self.sslcert.clone();
+        let sslkey = self.sslkey.clone();
+        let handle = self.runtime.handle().clone();
+        let idle = self.idle.clone();
+        let semaphore = self.semaphore.clone();
+        let timeout = self.timeout;
+        let max_idle = self.max_idle;
+        let mut owned_params = extract_params(args)?;
+
+        future_into_py(py, async move {
+            let (client, permit) = checkout(
+                &idle,
+                &semaphore,
+                &config,
+                sslmode,
+                sslrootcert.as_deref(),
+                sslcert.as_deref(),
+                sslkey.as_deref(),
+                &handle,
+                timeout,
+                max_idle,
+            )
+            .await?;
+
+            let result = run_execute(&client, &query, &mut owned_params).await;
+
+            // See the analogous check in `query` above: only a connection
+            // that's actually closed is unsafe to hand back to the pool.
+            if !client.is_closed() {
+                idle.lock().unwrap().push_back(IdleConn {
+                    client,
+                    idle_since: Instant::now(),
+                });
+            }
+            drop(permit);
+
+            result
+        })
+    }
+
+    /// Connections currently idle in the pool, available for immediate
+    /// reuse.
+    #[getter]
+    fn idle_count(&self) -> usize {
+        self.idle.lock().unwrap().len()
+    }
+
+    /// Connections currently checked out, either via `acquire()` or a
+    /// direct `query`/`execute` call in flight.
+    #[getter]
+    fn in_use_count(&self) -> usize {
+        self.max_size - self.semaphore.available_permits()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Pool(max_size={}, idle={}, in_use={})",
+            self.max_size,
+            self.idle_count(),
+            self.in_use_count()
+        )
+    }
+}
+
+/// Runs `query` against `client`, consuming and narrowing `owned_params`.
+/// Shared between `Pool::query` and `PooledConnection::query`, which both
+/// need the same prepare-refine-execute sequence but can't share a type
+/// encoder registry the way a live `Client` does.
+async fn run_query(
+    client: &PgClient,
+    query: &str,
+    owned_params: &mut Vec<OwnedParam>,
+) -> PyResult<Vec<tokio_postgres::Row>> {
+    let statement = client
+        .prepare(query)
+        .await
+        .map_err(|e| PyErr::from(OxpgError::from_db_error(&e)))?;
+
+    let empty_encoders: TypeHandlers = Arc::new(Mutex::new(HashMap::new()));
+    let empty_encoders_by_name: TypeHandlersByName = Arc::new(Mutex::new(HashMap::new()));
+    Python::attach(|py| {
+        let encoders = empty_encoders.lock().unwrap();
+        let encoders_by_name = empty_encoders_by_name.lock().unwrap();
+        refine_params(py, owned_params, &statement, &encoders, &encoders_by_name)
+    })?;
+    let ref_params: Vec<&(dyn ToSql + Sync)> = owned_params.iter().map(|p| p.as_ref()).collect();
+
+    client
+        .query(&statement, &ref_params)
+        .await
+        .map_err(|e| PyErr::from(OxpgError::from_db_error(&e)))
+}
+
+/// Like `run_query`, but for a statement run via `execute`, returning the
+/// affected-row count instead of rows.
+async fn run_execute(
+    client: &PgClient,
+    query: &str,
+    owned_params: &mut Vec<OwnedParam>,
+) -> PyResult<u64> {
+    let statement = client
+        .prepare(query)
+        .await
+        .map_err(|e| PyErr::from(OxpgError::from_db_error(&e)))?;
+
+    let empty_encoders: TypeHandlers = Arc::new(Mutex::new(HashMap::new()));
+    let empty_encoders_by_name: TypeHandlersByName = Arc::new(Mutex::new(HashMap::new()));
+    Python::attach(|py| {
+        let encoders = empty_encoders.lock().unwrap();
+        let encoders_by_name = empty_encoders_by_name.lock().unwrap();
+        refine_params(py, owned_params, &statement, &encoders, &encoders_by_name)
+    })?;
+    let ref_params: Vec<&(dyn ToSql + Sync)> = owned_params.iter().map(|p| p.as_ref()).collect();
+
+    client
+        .execute(&statement, &ref_params)
+        .await
+        .map_err(|e| PyErr::from(OxpgError::from_db_error(&e)))
+}
+
+/// A connection checked out of a `Pool`. Releases itself back to the pool
+/// when dropped, or when used as a context manager.
+#[gen_stub_pyclass]
+#[pyclass]
+pub struct PooledConnection {
+    client: Option<Arc<PgClient>>,
+    idle: Arc<Mutex<VecDeque<IdleConn>>>,
+    permit: Option<tokio::sync::OwnedSemaphorePermit>,
+}
+
+impl PooledConnection {
+    fn release(&mut self) {
+        if let Some(client) = self.client.take() {
+            // See the analogous check in `Pool::query`/`Pool::execute`: a
+            // connection that died while checked out is only safe to drop,
+            // not to hand back to the next `acquire()`.
+            if !client.is_closed() {
+                self.idle.lock().unwrap().push_back(IdleConn {
+                    client,
+                    idle_since: Instant::now(),
+                });
+            }
+        }
+        self.permit.take();
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PooledConnection {
+    #[pyo3(signature = (query, *args))]
+    fn query<'a>(
+        &'a self,
+        py: Python<'a>,
+        query: String,
+        args: &Bound<'a, PyTuple>,
+    ) -> PyResult<Bound<'a, PyAny>> {
+        let client = self
+            .client
+            .clone()
+            .ok_or_else(|| PyErr::from(OxpgError::Unexpected("connection already released".to_string())))?;
+        let mut owned_params = extract_params(args)?;
+        future_into_py(py, async move {
+            let rows = run_query(&client, &query, &mut owned_params).await?;
+
+            Python::attach(|py| -> PyResult<Py<PyAny>> {
+                let empty_decoders = HashMap::new();
+                let result = PyList::empty(py);
+                for row in rows {
+                    let py_row = row_to_dict(py, &row, &empty_decoders, true)?;
+                    result.append(py_row).map_err(|e| {
+                        PyErr::from(OxpgError::DataConversionError(format!(
+                            "Failed to append row to result list: {:?}",
+                            e
+                        )))
+                    })?;
+                }
+                Ok(result.into_any().unbind())
+            })
+        })
+    }
+
+    #[pyo3(signature = (query, *args))]
+    fn execute<'a>(
+        &'a self,
+        py: Python<'a>,
+        query: String,
+        args: &Bound<'a, PyTuple>,
+    ) -> PyResult<Bound<'a, PyAny>> {
+        let client = self
+            .client
+            .clone()
+            .ok_or_else(|| PyErr::from(OxpgError::Unexpected("connection already released".to_string())))?;
+        let mut owned_params = extract_params(args)?;
+        future_into_py(py, async move { run_execute(&client, &query, &mut owned_params).await })
+    }
+
+    /// Releases this connection back to the pool immediately instead of
+    /// waiting for it to be dropped.
+    fn release_now(&mut self) {
+        self.release();
+    }
+
+    fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __exit__(
+        &mut self,
+        _exc_type: Py<PyAny>,
+        _exc_value: Py<PyAny>,
+        _traceback: Py<PyAny>,
+    ) -> bool {
+        self.release();
+        false
+    }
+}
+
+/// Rejects a non-positive `max_size`, otherwise hands the value back
+/// unchanged.
+fn validate_max_size(max_size: usize) -> PyResult<usize> {
+    if max_size == 0 {
+        return Err(
+            OxpgError::InvalidParameter("max_size must be greater than zero".to_string()).into(),
+        );
+    }
+    Ok(max_size)
+}
+
+/// Builds a `Pool` of up to `max_size` live connections sharing one
+/// resolved `Config`, pre-warming `min_size` of them eagerly so the first
+/// `min_size` callers never pay connection-setup latency. Mirrors `connect`'s
+/// DSN/individual-parameter handling so both forms work here too. Shared
+/// between `connect_pool` and `Pool`'s own constructor, which both need to
+/// build a pool the same way.
+#[allow(clippy::too_many_arguments)]
+fn build_pool(
+    py: Python<'_>,
+    dsn: Option<String>,
+    host: Option<String>,
+    user: Option<String>,
+    password: Option<String>,
+    port: u16,
+    db: String,
+    sslmode: Option<String>,
+    sslrootcert: Option<String>,
+    sslcert: Option<String>,
+    sslkey: Option<String>,
+    min_size: usize,
+    max_size: usize,
+    max_idle: f64,
+    timeout: f64,
+) -> PyResult<Pool> {
+    validate_connect_params(&dsn, &host, &user, &password)?;
+
+    let max_size = validate_max_size(max_size)?;
+    if min_size > max_size {
+        return Err(
+            OxpgError::InvalidParameter("min_size must not exceed max_size".to_string()).into(),
+        );
+    }
+
+    let mut config = Config::new();
+
+    let (raw_sslmode, (_, _, _, _, config)) = match dsn {
+        Some(s) => (
+            raw_sslmode_from_dsn(&s),
+            extract_host_from_dsn(s, &mut config)?,
+        ),
+        None => {
+            let host = host.ok_or_else(|| OxpgError::MissingParameter("host".to_string()))?;
+            let user = user.ok_or_else(|| OxpgError::MissingParameter("user".to_string()))?;
+            let password =
+                password.ok_or_else(|| OxpgError::MissingParameter("password".to_string()))?;
+
+            let config = populate_config_from_params(
+                host.clone(),
+                user.clone(),
+                password,
+                port,
+                db.clone(),
+                &mut config,
+            );
+            (None, (host, user, port, db, config))
+        }
+    };
+
+    let sslmode = match sslmode.or(raw_sslmode) {
+        Some(s) => SslMode::parse(Some(&s))?,
+        None => SslMode::from_config_ssl_mode(config.get_ssl_mode()),
+    };
+    let config = config.clone();
+
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| {
+        PyErr::from(OxpgError::RuntimeFailed(format!(
+            "Failed to create Tokio runtime: {:?}",
+            e
+        )))
+    })?;
+
+    let handle = runtime.handle().clone();
+    let mut idle = VecDeque::new();
+    py.detach(|| -> PyResult<()> {
+        for _ in 0..min_size {
+            let client = runtime.block_on(open_connection(
+                &config,
+                sslmode,
+                sslrootcert.as_deref(),
+                sslcert.as_deref(),
+                sslkey.as_deref(),
+                &handle,
+                0,
+                0.0,
+                None,
+            ))?;
+            idle.push_back(IdleConn {
+                client,
+                idle_since: Instant::now(),
+            });
+        }
+        Ok(())
+    })?;
+
+    Ok(Pool {
+        config,
+        sslmode,
+        sslrootcert,
+        sslcert,
+        sslkey,
+        runtime: Arc::new(runtime),
+        idle: Arc::new(Mutex::new(idle)),
+        semaphore: Arc::new(tokio::sync::Semaphore::new(max_size)),
+        max_size,
+        timeout,
+        max_idle,
+    })
+}
+
+/// Builds a `Pool` the same way `Pool(...)` does, as a standalone function
+/// for callers who prefer `connect_pool(...)`'s `connect()`-style shape over
+/// constructing the class directly.
+#[gen_stub_pyfunction]
+#[pyfunction]
+#[pyo3(signature = (dsn=None, host=None, user=None, password=None, port=5432, db="postgres".to_string(), sslmode=None, sslrootcert=None, sslcert=None, sslkey=None, min_size=0, max_size=10, max_idle=300.0, timeout=30.0))]
+#[allow(clippy::too_many_arguments)]
+pub fn connect_pool(
+    py: Python<'_>,
+    dsn: Option<String>,
+    host: Option<String>,
+    user: Option<String>,
+    password: Option<String>,
+    port: u16,
+    db: String,
+    sslmode: Option<String>,
+    sslrootcert: Option<String>,
+    sslcert: Option<String>,
+    sslkey: Option<String>,
+    min_size: usize,
+    max_size: usize,
+    max_idle: f64,
+    timeout: f64,
+) -> PyResult<Pool> {
+    build_pool(
+        py,
+        dsn,
+        host,
+        user,
+        password,
+        port,
+        db,
+        sslmode,
+        sslrootcert,
+        sslcert,
+        sslkey,
+        min_size,
+        max_size,
+        max_idle,
+        timeout,
+    )
+}
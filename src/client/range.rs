@@ -0,0 +1,303 @@
+//! Binary encode/decode for Postgres range types (`int4range`, `tsrange`,
+//! `tstzrange`, `daterange`), exposed to Python as a small `Range` object
+//! carrying its bounds and inclusivity flags rather than a raw range
+//! literal string.
+
+use crate::client::conversions::datetime_arg_to_utc_naive;
+use crate::errors::OxpgError;
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+use pyo3::prelude::*;
+use pyo3::types::{PyDate, PyDateTime, PyInt};
+use pyo3_stub_gen::derive::*;
+use tokio_postgres::types::private::BytesMut;
+
+const RANGE_EMPTY: u8 = 0x01;
+const RANGE_LB_INC: u8 = 0x02;
+const RANGE_UB_INC: u8 = 0x04;
+const RANGE_LB_INF: u8 = 0x08;
+const RANGE_UB_INF: u8 = 0x10;
+
+fn postgres_epoch_date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2000, 1, 1).unwrap()
+}
+
+fn postgres_epoch() -> NaiveDateTime {
+    postgres_epoch_date().and_hms_opt(0, 0, 0).unwrap()
+}
+
+/// A range bound value, carrying enough of its own type to re-encode it
+/// without needing the target column's OID (unlike ordinary scalar
+/// parameters, which `refine_params` narrows once the OID is known).
+#[derive(Debug, Clone)]
+pub(crate) enum RangeBound {
+    Int4(i32),
+    Timestamp(NaiveDateTime),
+    TimestampTz(DateTime<Utc>),
+    Date(NaiveDate),
+}
+
+impl RangeBound {
+    fn write(&self, out: &mut BytesMut) {
+        match self {
+            RangeBound::Int4(n) => {
+                out.extend_from_slice(&4i32.to_be_bytes());
+                out.extend_from_slice(&n.to_be_bytes());
+            }
+            RangeBound::Timestamp(dt) => {
+                let micros = (*dt - postgres_epoch())
+                    .num_microseconds()
+                    .unwrap_or(0);
+                out.extend_from_slice(&8i32.to_be_bytes());
+                out.extend_from_slice(&micros.to_be_bytes());
+            }
+            RangeBound::TimestampTz(dt) => {
+                let micros = (dt.naive_utc() - postgres_epoch())
+                    .num_microseconds()
+                    .unwrap_or(0);
+                out.extend_from_slice(&8i32.to_be_bytes());
+                out.extend_from_slice(&micros.to_be_bytes());
+            }
+            RangeBound::Date(d) => {
+                let days = (*d - postgres_epoch_date()).num_days() as i32;
+                out.extend_from_slice(&4i32.to_be_bytes());
+                out.extend_from_slice(&days.to_be_bytes());
+            }
+        }
+    }
+}
+
+/// Writes a range's wire representation (flags byte, then each present
+/// bound as a length-prefixed value), the same layout `decode` reads back.
+pub(crate) fn encode(
+    lower: &Option<RangeBound>,
+    upper: &Option<RangeBound>,
+    lower_inclusive: bool,
+    upper_inclusive: bool,
+    empty: bool,
+    out: &mut BytesMut,
+) {
+    let mut flags = 0u8;
+    if empty {
+        flags |= RANGE_EMPTY;
+    }
+    if lower_inclusive {
+        flags |= RANGE_LB_INC;
+    }
+    if upper_inclusive {
+        flags |= RANGE_UB_INC;
+    }
+    if lower.is_none() {
+        flags |= RANGE_LB_INF;
+    }
+    if upper.is_none() {
+        flags |= RANGE_UB_INF;
+    }
+
+    out.extend_from_slice(&[flags]);
+
+    if empty {
+        return;
+    }
+    if let Some(bound) = lower {
+        bound.write(out);
+    }
+    if let Some(bound) = upper {
+        bound.write(out);
+    }
+}
+
+/// Parses a range's wire representation, handing each present bound's raw
+/// bytes to `parse_bound` to turn into a Python value.
+fn decode<F>(raw: &[u8], mut parse_bound: F) -> Result<Range, OxpgError>
+where
+    F: FnMut(&[u8]) -> Result<Py<PyAny>, OxpgError>,
+{
+    let invalid = || OxpgError::DataConversionError("malformed range value".to_string());
+
+    let (&flags, mut rest) = raw.split_first().ok_or_else(invalid)?;
+
+    if flags & RANGE_EMPTY != 0 {
+        return Ok(Range {
+            lower: None,
+            upper: None,
+            lower_inclusive: false,
+            upper_inclusive: false,
+            empty: true,
+        });
+    }
+
+    let mut read_bound = |rest: &mut &[u8]| -> Result<Py<PyAny>, OxpgError> {
+        if rest.len() < 4 {
+            return Err(invalid());
+        }
+        let (len_bytes, after_len) = rest.split_at(4);
+        let len = i32::from_be_bytes(len_bytes.try_into().unwrap()) as usize;
+        let value_bytes = after_len.get(..len).ok_or_else(invalid)?;
+        *rest = &after_len[len..];
+        parse_bound(value_bytes)
+    };
+
+    let lower = if flags & RANGE_LB_INF != 0 {
+        None
+    } else {
+        Some(read_bound(&mut rest)?)
+    };
+    let upper = if flags & RANGE_UB_INF != 0 {
+        None
+    } else {
+        Some(read_bound(&mut rest)?)
+    };
+
+    Ok(Range {
+        lower,
+        upper,
+        lower_inclusive: flags & RANGE_LB_INC != 0,
+        upper_inclusive: flags & RANGE_UB_INC != 0,
+        empty: false,
+    })
+}
+
+pub(crate) fn decode_int4range(py: Python<'_>, raw: &[u8]) -> PyResult<Range> {
+    decode(raw, |bytes| {
+        let arr: [u8; 4] = bytes
+            .try_into()
+            .map_err(|_| OxpgError::DataConversionError("invalid int4range bound".to_string()))?;
+        let n = i32::from_be_bytes(arr);
+        n.into_pyobject(py)
+            .map(|v| v.into_any().unbind())
+            .map_err(|e| OxpgError::DataConversionError(format!("{:?}", e)))
+    })
+    .map_err(PyErr::from)
+}
+
+pub(crate) fn decode_tsrange(py: Python<'_>, raw: &[u8]) -> PyResult<Range> {
+    decode(raw, |bytes| {
+        let arr: [u8; 8] = bytes
+            .try_into()
+            .map_err(|_| OxpgError::DataConversionError("invalid tsrange bound".to_string()))?;
+        let micros = i64::from_be_bytes(arr);
+        let dt = postgres_epoch() + Duration::microseconds(micros);
+        dt.into_pyobject(py)
+            .map(|v| v.into_any().unbind())
+            .map_err(|e| OxpgError::DataConversionError(format!("{:?}", e)))
+    })
+    .map_err(PyErr::from)
+}
+
+pub(crate) fn decode_tstzrange(py: Python<'_>, raw: &[u8]) -> PyResult<Range> {
+    decode(raw, |bytes| {
+        let arr: [u8; 8] = bytes
+            .try_into()
+            .map_err(|_| OxpgError::DataConversionError("invalid tstzrange bound".to_string()))?;
+        let micros = i64::from_be_bytes(arr);
+        let dt = DateTime::<Utc>::from_naive_utc_and_offset(
+            postgres_epoch() + Duration::microseconds(micros),
+            Utc,
+        );
+        dt.into_pyobject(py)
+            .map(|v| v.into_any().unbind())
+            .map_err(|e| OxpgError::DataConversionError(format!("{:?}", e)))
+    })
+    .map_err(PyErr::from)
+}
+
+pub(crate) fn decode_daterange(py: Python<'_>, raw: &[u8]) -> PyResult<Range> {
+    decode(raw, |bytes| {
+        let arr: [u8; 4] = bytes
+            .try_into()
+            .map_err(|_| OxpgError::DataConversionError("invalid daterange bound".to_string()))?;
+        let days = i32::from_be_bytes(arr);
+        let date = postgres_epoch_date() + Duration::days(days as i64);
+        date.into_pyobject(py)
+            .map(|v| v.into_any().unbind())
+            .map_err(|e| OxpgError::DataConversionError(format!("{:?}", e)))
+    })
+    .map_err(PyErr::from)
+}
+
+/// Inspects a Python value pulled out of `Range.lower`/`Range.upper` and
+/// turns it into the `RangeBound` variant matching its own type (mirroring
+/// how array parameters infer their element type from the first value).
+pub(crate) fn bound_from_pyobject(value: &Bound<'_, PyAny>) -> PyResult<RangeBound> {
+    if value.is_instance_of::<PyInt>() {
+        Ok(RangeBound::Int4(value.extract()?))
+    } else if value.is_instance_of::<PyDateTime>() {
+        let tzinfo = value.getattr("tzinfo")?;
+        if tzinfo.is_none() {
+            Ok(RangeBound::Timestamp(value.extract()?))
+        } else {
+            let utc_naive = datetime_arg_to_utc_naive(value)?;
+            Ok(RangeBound::TimestampTz(DateTime::<Utc>::from_naive_utc_and_offset(
+                utc_naive, Utc,
+            )))
+        }
+    } else if value.is_instance_of::<PyDate>() {
+        Ok(RangeBound::Date(value.extract()?))
+    } else {
+        Err(OxpgError::UnsupportedType(format!(
+            "Range bound of type '{}' is not supported; expected int, date, or datetime",
+            value.get_type().name()?
+        ))
+        .into())
+    }
+}
+
+/// A decoded or to-be-encoded Postgres range value (`int4range`, `tsrange`,
+/// ...). `lower`/`upper` are `None` for an unbounded (`-infinity`/
+/// `infinity`) side, or for an empty range (where `empty` is `true`).
+#[gen_stub_pyclass]
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct Range {
+    #[pyo3(get)]
+    pub(crate) lower: Option<Py<PyAny>>,
+    #[pyo3(get)]
+    pub(crate) upper: Option<Py<PyAny>>,
+    #[pyo3(get)]
+    pub(crate) lower_inclusive: bool,
+    #[pyo3(get)]
+    pub(crate) upper_inclusive: bool,
+    #[pyo3(get)]
+    pub(crate) empty: bool,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl Range {
+    #[new]
+    #[pyo3(signature = (lower=None, upper=None, lower_inclusive=true, upper_inclusive=false, empty=false))]
+    fn new(
+        lower: Option<Py<PyAny>>,
+        upper: Option<Py<PyAny>>,
+        lower_inclusive: bool,
+        upper_inclusive: bool,
+        empty: bool,
+    ) -> Range {
+        Range {
+            lower,
+            upper,
+            lower_inclusive,
+            upper_inclusive,
+            empty,
+        }
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        if self.empty {
+            return Ok("Range(empty=True)".to_string());
+        }
+        let fmt_bound = |b: &Option<Py<PyAny>>| -> PyResult<String> {
+            match b {
+                Some(v) => Ok(v.bind(py).repr()?.to_string()),
+                None => Ok("None".to_string()),
+            }
+        };
+        Ok(format!(
+            "Range(lower={}, upper={}, lower_inclusive={}, upper_inclusive={})",
+            fmt_bound(&self.lower)?,
+            fmt_bound(&self.upper)?,
+            self.lower_inclusive,
+            self.upper_inclusive
+        ))
+    }
+}
@@ -0,0 +1,121 @@
+//! TLS connector construction for the `sslmode` family of `connect` options.
+
+use crate::errors::OxpgError;
+use native_tls::{Certificate, Identity, TlsConnector};
+use postgres_native_tls::MakeTlsConnector;
+use std::fs;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SslMode {
+    Disable,
+    Prefer,
+    Require,
+    VerifyCa,
+    VerifyFull,
+}
+
+impl SslMode {
+    /// Parses the `sslmode` keyword argument, defaulting to `prefer` (libpq's
+    /// own default) when the caller didn't pass one explicitly.
+    pub fn parse(raw: Option<&str>) -> Result<SslMode, OxpgError> {
+        match raw {
+            Some(s) => s.parse(),
+            None => Ok(SslMode::Prefer),
+        }
+    }
+
+    /// Maps `tokio_postgres`'s own three-value `SslMode` (as parsed out of a
+    /// DSN's `sslmode=` query parameter) onto ours, used as the fallback when
+    /// `connect` wasn't given an explicit `sslmode` keyword.
+    pub fn from_config_ssl_mode(mode: tokio_postgres::config::SslMode) -> SslMode {
+        match mode {
+            tokio_postgres::config::SslMode::Disable => SslMode::Disable,
+            tokio_postgres::config::SslMode::Require => SslMode::Require,
+            _ => SslMode::Prefer,
+        }
+    }
+}
+
+impl FromStr for SslMode {
+    type Err = OxpgError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "disable" => Ok(SslMode::Disable),
+            "prefer" => Ok(SslMode::Prefer),
+            "require" => Ok(SslMode::Require),
+            "verify-ca" => Ok(SslMode::VerifyCa),
+            "verify-full" => Ok(SslMode::VerifyFull),
+            other => Err(OxpgError::InvalidParameter(format!(
+                "Unknown sslmode '{}': expected one of disable, prefer, require, verify-ca, verify-full",
+                other
+            ))),
+        }
+    }
+}
+
+/// Builds a `postgres-native-tls` connector honoring the libpq `sslmode`
+/// ladder. `disable` never reaches here -- callers should use
+/// `tokio_postgres::NoTls` directly for that mode.
+pub fn build_connector(
+    mode: SslMode,
+    sslrootcert: Option<&str>,
+    sslcert: Option<&str>,
+    sslkey: Option<&str>,
+) -> Result<MakeTlsConnector, OxpgError> {
+    let mut builder = TlsConnector::builder();
+
+    match mode {
+        // Neither mode verifies the server's certificate chain or
+        // hostname. `prefer` falls back to an unencrypted connection when
+        // the server doesn't speak TLS at all, while `require` (like the
+        // verify modes) fails instead -- `open_connection` sets the
+        // `Config`'s own `ssl_mode` to match.
+        SslMode::Prefer | SslMode::Require => {
+            builder.danger_accept_invalid_certs(true);
+            builder.danger_accept_invalid_hostnames(true);
+        }
+        // `verify-ca` checks the chain against the supplied root but
+        // doesn't insist the hostname matches the certificate's SAN.
+        SslMode::VerifyCa => {
+            builder.danger_accept_invalid_hostnames(true);
+        }
+        // `verify-full` does the whole job: chain plus hostname/SAN.
+        SslMode::VerifyFull => {}
+        SslMode::Disable => {
+            return Err(OxpgError::Unexpected(
+                "build_connector called with sslmode=disable".to_string(),
+            ));
+        }
+    }
+
+    if let Some(path) = sslrootcert {
+        let pem = fs::read(path).map_err(|e| {
+            OxpgError::InvalidParameter(format!("Failed to read sslrootcert '{}': {}", path, e))
+        })?;
+        let cert = Certificate::from_pem(&pem).map_err(|e| {
+            OxpgError::InvalidParameter(format!("Invalid sslrootcert '{}': {}", path, e))
+        })?;
+        builder.add_root_certificate(cert);
+    }
+
+    if let (Some(cert_path), Some(key_path)) = (sslcert, sslkey) {
+        let cert_pem = fs::read(cert_path).map_err(|e| {
+            OxpgError::InvalidParameter(format!("Failed to read sslcert '{}': {}", cert_path, e))
+        })?;
+        let key_pem = fs::read(key_path).map_err(|e| {
+            OxpgError::InvalidParameter(format!("Failed to read sslkey '{}': {}", key_path, e))
+        })?;
+        let identity = Identity::from_pkcs8(&cert_pem, &key_pem).map_err(|e| {
+            OxpgError::InvalidParameter(format!("Invalid client certificate/key: {}", e))
+        })?;
+        builder.identity(identity);
+    }
+
+    let connector = builder
+        .build()
+        .map_err(|e| OxpgError::ConnectionFailed(format!("Failed to build TLS connector: {}", e)))?;
+
+    Ok(MakeTlsConnector::new(connector))
+}
@@ -0,0 +1,336 @@
+//! Transaction and savepoint management. A `Transaction` returned by
+//! `Client.transaction()` supports `async with` semantics directly:
+//! `commit()` on a clean exit, `rollback()` on an exception. Nested scopes
+//! opened via `savepoint()` layer `SAVEPOINT`/`RELEASE SAVEPOINT`/
+//! `ROLLBACK TO SAVEPOINT` on top of the same underlying session instead
+//! of a real `BEGIN`.
+
+use std::sync::{Arc, Mutex};
+
+use crate::client::conversions::{extract_params, refine_params, row_to_dict};
+use crate::client::{prepare_cached, StatementCache, TypeHandlers, TypeHandlersByName};
+use crate::errors::OxpgError;
+use pyo3::prelude::*;
+use pyo3::types::{PyList, PyTuple};
+use pyo3_async_runtimes::tokio::future_into_py;
+use pyo3_stub_gen::derive::*;
+use tokio_postgres::types::ToSql;
+use tokio_postgres::Client as PgClient;
+
+/// Validates an isolation level name and maps it onto the SQL keywords
+/// `BEGIN ISOLATION LEVEL` expects (isolation level can't be bound as an
+/// ordinary query parameter).
+pub(crate) fn isolation_level_clause(raw: &str) -> Result<&'static str, OxpgError> {
+    match raw {
+        "read committed" => Ok("READ COMMITTED"),
+        "repeatable read" => Ok("REPEATABLE READ"),
+        "serializable" => Ok("SERIALIZABLE"),
+        other => Err(OxpgError::InvalidParameter(format!(
+            "Unknown isolation level '{}': expected one of read committed, repeatable read, serializable",
+            other
+        ))),
+    }
+}
+
+/// Builds the `BEGIN ...` statement text for the given transaction options.
+pub(crate) fn build_begin_statement(
+    isolation_level: Option<&str>,
+    read_only: Option<bool>,
+    deferrable: Option<bool>,
+) -> Result<String, OxpgError> {
+    let mut sql = String::from("BEGIN");
+
+    if let Some(level) = isolation_level {
+        sql.push_str(" ISOLATION LEVEL ");
+        sql.push_str(isolation_level_clause(level)?);
+    }
+
+    if let Some(read_only) = read_only {
+        sql.push_str(if read_only { " READ ONLY" } else { " READ WRITE" });
+    }
+
+    if let Some(deferrable) = deferrable {
+        sql.push_str(if deferrable {
+            " DEFERRABLE"
+        } else {
+            " NOT DEFERRABLE"
+        });
+    }
+
+    Ok(sql)
+}
+
+/// Issues `BEGIN` with the given options on `client`.
+pub(crate) async fn begin(
+    client: &PgClient,
+    isolation_level: Option<&str>,
+    read_only: Option<bool>,
+    deferrable: Option<bool>,
+) -> PyResult<()> {
+    let sql = build_begin_statement(isolation_level, read_only, deferrable)?;
+    client
+        .batch_execute(&sql)
+        .await
+        .map_err(|e| PyErr::from(OxpgError::from_db_error(&e)))?;
+    Ok(())
+}
+
+/// A transaction (or nested savepoint) opened on a `Client`. Auto-generated
+/// savepoint names are `oxpg_sp_<n>`, with `<n>` coming from a counter
+/// shared with the top-level transaction so sibling/nested scopes never
+/// collide.
+#[gen_stub_pyclass]
+#[pyclass]
+pub struct Transaction {
+    client: Arc<PgClient>,
+    statement_cache: StatementCache,
+    type_decoders: TypeHandlers,
+    type_encoders: TypeHandlers,
+    type_encoders_by_name: TypeHandlersByName,
+    native_types: bool,
+    savepoint_name: Option<String>,
+    next_savepoint_id: Arc<Mutex<u32>>,
+    finished: Arc<Mutex<bool>>,
+}
+
+impl Transaction {
+    pub(crate) fn new_top_level(
+        client: Arc<PgClient>,
+        statement_cache: StatementCache,
+        type_decoders: TypeHandlers,
+        type_encoders: TypeHandlers,
+        type_encoders_by_name: TypeHandlersByName,
+        native_types: bool,
+    ) -> Transaction {
+        Transaction {
+            client,
+            statement_cache,
+            type_decoders,
+            type_encoders,
+            type_encoders_by_name,
+            native_types,
+            savepoint_name: None,
+            next_savepoint_id: Arc::new(Mutex::new(0)),
+            finished: Arc::new(Mutex::new(false)),
+        }
+    }
+
+    /// Marks this transaction finished, returning whether this call is the
+    /// one that got to do it (`false` means some earlier call already did).
+    fn try_mark_finished(&self) -> bool {
+        let mut finished = self.finished.lock().unwrap();
+        if *finished {
+            return false;
+        }
+        *finished = true;
+        true
+    }
+
+    fn mark_finished(&self) -> PyResult<()> {
+        if self.try_mark_finished() {
+            Ok(())
+        } else {
+            Err(OxpgError::InvalidParameter(
+                "transaction has already been committed or rolled back".to_string(),
+            )
+            .into())
+        }
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl Transaction {
+    #[pyo3(signature = (query, *args))]
+    fn query<'a>(
+        &'a self,
+        py: Python<'a>,
+        query: String,
+        args: &Bound<'a, PyTuple>,
+    ) -> PyResult<Bound<'a, PyAny>> {
+        let client = self.client.clone();
+        let statement_cache = self.statement_cache.clone();
+        let type_decoders = self.type_decoders.clone();
+        let type_encoders = self.type_encoders.clone();
+        let type_encoders_by_name = self.type_encoders_by_name.clone();
+        let native_types = self.native_types;
+        let mut owned_params = extract_params(args)?;
+        future_into_py(py, async move {
+            let statement = prepare_cached(&client, &statement_cache, &query)
+                .await
+                .map_err(|e| PyErr::from(OxpgError::from_db_error(&e)))?;
+
+            Python::attach(|py| {
+                let encoders = type_encoders.lock().unwrap();
+                let encoders_by_name = type_encoders_by_name.lock().unwrap();
+                refine_params(py, &mut owned_params, &statement, &encoders, &encoders_by_name)
+            })?;
+            let ref_params: Vec<&(dyn ToSql + Sync)> =
+                owned_params.iter().map(|p| p.as_ref()).collect();
+
+            let rows = client
+                .query(statement.as_ref(), &ref_params)
+                .await
+                .map_err(|e| PyErr::from(OxpgError::from_db_error(&e)))?;
+
+            Python::attach(|py| -> PyResult<Py<PyAny>> {
+                let decoders = type_decoders.lock().unwrap();
+                let result = PyList::empty(py);
+                for row in rows {
+                    let py_row = row_to_dict(py, &row, &decoders, native_types)?;
+                    result.append(py_row).map_err(|e| {
+                        PyErr::from(OxpgError::DataConversionError(format!(
+                            "Failed to append row to result list: {:?}",
+                            e
+                        )))
+                    })?;
+                }
+                Ok(result.into_any().unbind())
+            })
+        })
+    }
+
+    #[pyo3(signature = (query, *args))]
+    fn execute<'a>(
+        &'a self,
+        py: Python<'a>,
+        query: String,
+        args: &Bound<'a, PyTuple>,
+    ) -> PyResult<Bound<'a, PyAny>> {
+        let client = self.client.clone();
+        let statement_cache = self.statement_cache.clone();
+        let type_encoders = self.type_encoders.clone();
+        let type_encoders_by_name = self.type_encoders_by_name.clone();
+        let mut owned_params = extract_params(args)?;
+        future_into_py(py, async move {
+            let statement = prepare_cached(&client, &statement_cache, &query)
+                .await
+                .map_err(|e| PyErr::from(OxpgError::from_db_error(&e)))?;
+
+            Python::attach(|py| {
+                let encoders = type_encoders.lock().unwrap();
+                let encoders_by_name = type_encoders_by_name.lock().unwrap();
+                refine_params(py, &mut owned_params, &statement, &encoders, &encoders_by_name)
+            })?;
+            let ref_params: Vec<&(dyn ToSql + Sync)> =
+                owned_params.iter().map(|p| p.as_ref()).collect();
+
+            let result = client
+                .execute(statement.as_ref(), &ref_params)
+                .await
+                .map_err(|e| PyErr::from(OxpgError::from_db_error(&e)))?;
+
+            Ok(result)
+        })
+    }
+
+    /// Begins a nested `SAVEPOINT` scope on top of this transaction.
+    fn savepoint<'a>(&'a self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let client = self.client.clone();
+        let statement_cache = self.statement_cache.clone();
+        let type_decoders = self.type_decoders.clone();
+        let type_encoders = self.type_encoders.clone();
+        let type_encoders_by_name = self.type_encoders_by_name.clone();
+        let native_types = self.native_types;
+        let next_savepoint_id = self.next_savepoint_id.clone();
+
+        future_into_py(py, async move {
+            let id = {
+                let mut id = next_savepoint_id.lock().unwrap();
+                *id += 1;
+                *id
+            };
+            let name = format!("oxpg_sp_{}", id);
+
+            client
+                .batch_execute(&format!("SAVEPOINT {}", name))
+                .await
+                .map_err(|e| PyErr::from(OxpgError::from_db_error(&e)))?;
+
+            Ok(Transaction {
+                client,
+                statement_cache,
+                type_decoders,
+                type_encoders,
+                type_encoders_by_name,
+                native_types,
+                savepoint_name: Some(name),
+                next_savepoint_id,
+                finished: Arc::new(Mutex::new(false)),
+            })
+        })
+    }
+
+    /// Commits this transaction (`RELEASE SAVEPOINT` for a nested one).
+    /// Errors if it was already committed or rolled back.
+    fn commit<'a>(&'a self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        self.mark_finished()?;
+        let client = self.client.clone();
+        let sql = match &self.savepoint_name {
+            Some(name) => format!("RELEASE SAVEPOINT {}", name),
+            None => "COMMIT".to_string(),
+        };
+        future_into_py(py, async move {
+            client
+                .batch_execute(&sql)
+                .await
+                .map_err(|e| PyErr::from(OxpgError::from_db_error(&e)))
+        })
+    }
+
+    /// Rolls back this transaction (`ROLLBACK TO SAVEPOINT` for a nested
+    /// one). Errors if it was already committed or rolled back.
+    fn rollback<'a>(&'a self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        self.mark_finished()?;
+        let client = self.client.clone();
+        let sql = match &self.savepoint_name {
+            Some(name) => format!("ROLLBACK TO SAVEPOINT {}", name),
+            None => "ROLLBACK".to_string(),
+        };
+        future_into_py(py, async move {
+            client
+                .batch_execute(&sql)
+                .await
+                .map_err(|e| PyErr::from(OxpgError::from_db_error(&e)))
+        })
+    }
+
+    fn __aenter__<'a>(slf: Py<Self>, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        future_into_py(py, async move { Ok(slf) })
+    }
+
+    /// Commits on a clean exit, rolls back if the `async with` block raised.
+    /// A no-op if `commit()`/`rollback()` was already called explicitly
+    /// inside the block. Never suppresses the exception.
+    #[pyo3(signature = (exc_type, _exc_value, _traceback))]
+    fn __aexit__<'a>(
+        &'a self,
+        py: Python<'a>,
+        exc_type: Py<PyAny>,
+        _exc_value: Py<PyAny>,
+        _traceback: Py<PyAny>,
+    ) -> PyResult<Bound<'a, PyAny>> {
+        let client = self.client.clone();
+        let sql = if self.try_mark_finished() {
+            let failed = !exc_type.is_none(py);
+            Some(match (&self.savepoint_name, failed) {
+                (Some(name), true) => format!("ROLLBACK TO SAVEPOINT {}", name),
+                (Some(name), false) => format!("RELEASE SAVEPOINT {}", name),
+                (None, true) => "ROLLBACK".to_string(),
+                (None, false) => "COMMIT".to_string(),
+            })
+        } else {
+            None
+        };
+
+        future_into_py(py, async move {
+            if let Some(sql) = sql {
+                client
+                    .batch_execute(&sql)
+                    .await
+                    .map_err(|e| PyErr::from(OxpgError::from_db_error(&e)))?;
+            }
+            Ok(false)
+        })
+    }
+}
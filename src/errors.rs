@@ -1,3 +1,4 @@
+use crate::sqlstate::{self, ExceptionClass};
 use pyo3::exceptions::PyException;
 use pyo3::prelude::*;
 use pyo3_stub_gen::create_exception;
@@ -20,6 +21,13 @@ pub enum OxpgError {
     QueryFailed(String),
     #[error("Database execution failed: {0}")]
     ExecutionError(String),
+    #[error("{sqlstate}: {message}")]
+    Database {
+        sqlstate: String,
+        message: String,
+        detail: Option<String>,
+        hint: Option<String>,
+    },
     #[error("Unsupported Python type: {0}")]
     UnsupportedType(String),
     #[error("Data conversion failed: {0}")]
@@ -29,6 +37,24 @@ pub enum OxpgError {
     Unexpected(String),
 }
 
+impl OxpgError {
+    /// Builds an `OxpgError::Database` out of a `tokio_postgres::Error`,
+    /// pulling the SQLSTATE code plus message/detail/hint out of the
+    /// underlying `DbError` when the server actually reported one (as
+    /// opposed to e.g. a connection-level I/O error).
+    pub fn from_db_error(err: &tokio_postgres::Error) -> OxpgError {
+        match err.as_db_error() {
+            Some(db_error) => OxpgError::Database {
+                sqlstate: db_error.code().code().to_string(),
+                message: db_error.message().to_string(),
+                detail: db_error.detail().map(str::to_string),
+                hint: db_error.hint().map(str::to_string),
+            },
+            None => OxpgError::ExecutionError(format!("{:?}", err)),
+        }
+    }
+}
+
 create_exception!(oxpg, Error, PyException);
 
 create_exception!(oxpg, InterfaceError, Error);
@@ -39,8 +65,24 @@ create_exception!(oxpg, DataError, DatabaseError);
 
 create_exception!(oxpg, OperationalError, DatabaseError);
 
+create_exception!(oxpg, IntegrityError, DatabaseError);
+
+create_exception!(oxpg, ProgrammingError, DatabaseError);
+
 create_exception!(oxpg, InternalError, DatabaseError);
 
+create_exception!(oxpg, UniqueViolation, IntegrityError);
+
+create_exception!(oxpg, ForeignKeyViolation, IntegrityError);
+
+create_exception!(oxpg, NotNullViolation, IntegrityError);
+
+create_exception!(oxpg, SerializationFailure, OperationalError);
+
+create_exception!(oxpg, DeadlockDetected, OperationalError);
+
+create_exception!(oxpg, TransactionRollbackError, OperationalError);
+
 impl From<OxpgError> for PyErr {
     fn from(err: OxpgError) -> PyErr {
         match err {
@@ -53,6 +95,12 @@ impl From<OxpgError> for PyErr {
 
             OxpgError::QueryFailed(msg) => DatabaseError::new_err(msg),
             OxpgError::ExecutionError(msg) => DatabaseError::new_err(msg),
+            OxpgError::Database {
+                sqlstate,
+                message,
+                detail,
+                hint,
+            } => db_error_to_py_err(sqlstate, message, detail, hint),
 
             OxpgError::UnsupportedType(msg) => DataError::new_err(msg),
             OxpgError::DataConversionError(msg) => DataError::new_err(msg),
@@ -62,13 +110,67 @@ impl From<OxpgError> for PyErr {
     }
 }
 
+/// Raises the exception class matching the SQLSTATE's class, with the raw
+/// `sqlstate`, `message`, `detail`, and `hint` attached as attributes so
+/// callers can branch on error type without re-parsing `str(err)`.
+fn db_error_to_py_err(
+    sqlstate: String,
+    message: String,
+    detail: Option<String>,
+    hint: Option<String>,
+) -> PyErr {
+    let err = match sqlstate::classify(&sqlstate) {
+        ExceptionClass::UniqueViolation => UniqueViolation::new_err(message.clone()),
+        ExceptionClass::ForeignKeyViolation => ForeignKeyViolation::new_err(message.clone()),
+        ExceptionClass::NotNullViolation => NotNullViolation::new_err(message.clone()),
+        ExceptionClass::SerializationFailure => SerializationFailure::new_err(message.clone()),
+        ExceptionClass::DeadlockDetected => DeadlockDetected::new_err(message.clone()),
+        ExceptionClass::IntegrityError => IntegrityError::new_err(message.clone()),
+        ExceptionClass::OperationalError => OperationalError::new_err(message.clone()),
+        ExceptionClass::ProgrammingError => ProgrammingError::new_err(message.clone()),
+        ExceptionClass::DataError => DataError::new_err(message.clone()),
+        ExceptionClass::TransactionRollbackError => {
+            TransactionRollbackError::new_err(message.clone())
+        }
+        ExceptionClass::InternalError => InternalError::new_err(message.clone()),
+    };
+
+    Python::attach(|py| {
+        let value = err.value(py);
+        let _ = value.setattr("sqlstate", &sqlstate);
+        let _ = value.setattr("errcode", &sqlstate);
+        let _ = value.setattr("message", &message);
+        let _ = value.setattr("detail", detail);
+        let _ = value.setattr("hint", hint);
+    });
+
+    err
+}
+
 pub fn register_exceptions(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add("Error", m.py().get_type::<Error>())?;
     m.add("InterfaceError", m.py().get_type::<InterfaceError>())?;
     m.add("DatabaseError", m.py().get_type::<DatabaseError>())?;
     m.add("DataError", m.py().get_type::<DataError>())?;
     m.add("OperationalError", m.py().get_type::<OperationalError>())?;
+    m.add("IntegrityError", m.py().get_type::<IntegrityError>())?;
+    m.add("ProgrammingError", m.py().get_type::<ProgrammingError>())?;
     m.add("InternalError", m.py().get_type::<InternalError>())?;
+    m.add("UniqueViolation", m.py().get_type::<UniqueViolation>())?;
+    m.add(
+        "ForeignKeyViolation",
+        m.py().get_type::<ForeignKeyViolation>(),
+    )?;
+    m.add("NotNullViolation", m.py().get_type::<NotNullViolation>())?;
+    m.add(
+        "SerializationFailure",
+        m.py().get_type::<SerializationFailure>(),
+    )?;
+    m.add("DeadlockDetected", m.py().get_type::<DeadlockDetected>())?;
+    m.add(
+        "TransactionRollbackError",
+        m.py().get_type::<TransactionRollbackError>(),
+    )?;
 
     Ok(())
 }
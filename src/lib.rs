@@ -1,4 +1,6 @@
 mod client;
+mod errors;
+mod sqlstate;
 
 use pyo3::prelude::*;
 use pyo3_stub_gen::define_stub_info_gatherer;
@@ -8,7 +10,17 @@ use pyo3_stub_gen::derive::*;
 fn oxpg(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(client::connect, m)?)?;
     m.add_class::<client::Client>()?;
+    m.add_class::<client::PreparedStatement>()?;
+    m.add_class::<client::CancelHandle>()?;
+    m.add_function(wrap_pyfunction!(client::connect_pool, m)?)?;
+    m.add_class::<client::Pool>()?;
+    m.add_class::<client::PooledConnection>()?;
+    m.add_class::<client::Notification>()?;
+    m.add_class::<client::NotificationStream>()?;
+    m.add_class::<client::Transaction>()?;
+    m.add_class::<client::Range>()?;
     m.add_function(wrap_pyfunction!(sum_as_string, m)?)?;
+    errors::register_exceptions(m)?;
     Ok(())
 }
 
@@ -0,0 +1,94 @@
+//! PostgreSQL SQLSTATE code classification.
+//!
+//! `CLASSES` is a generated lookup table (the codes are taken straight from
+//! the Postgres documentation's "Appendix A. PostgreSQL Error Codes") mapping
+//! the five-character SQLSTATE to the DB-API-style exception class it should
+//! raise as in Python. Codes that aren't worth their own Python exception
+//! still get a sensible class via their two-character prefix in `classify`.
+
+use phf::phf_map;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExceptionClass {
+    UniqueViolation,
+    ForeignKeyViolation,
+    NotNullViolation,
+    SerializationFailure,
+    DeadlockDetected,
+    IntegrityError,
+    OperationalError,
+    ProgrammingError,
+    DataError,
+    TransactionRollbackError,
+    InternalError,
+}
+
+pub static CLASSES: phf::Map<&'static str, ExceptionClass> = phf_map! {
+    "23505" => ExceptionClass::UniqueViolation,
+    "23503" => ExceptionClass::ForeignKeyViolation,
+    "23502" => ExceptionClass::NotNullViolation,
+    "40001" => ExceptionClass::SerializationFailure,
+    "40P01" => ExceptionClass::DeadlockDetected,
+};
+
+/// Classifies a five-character SQLSTATE code, falling back to its
+/// two-character class prefix when there's no dedicated entry in `CLASSES`.
+pub fn classify(code: &str) -> ExceptionClass {
+    if let Some(&class) = CLASSES.get(code) {
+        return class;
+    }
+
+    match code.get(0..2) {
+        Some("23") => ExceptionClass::IntegrityError,
+        Some("22") => ExceptionClass::DataError,
+        Some("42") | Some("26") | Some("27") | Some("2B") | Some("38") | Some("39")
+        | Some("3F") => ExceptionClass::ProgrammingError,
+        Some("08") | Some("57") | Some("53") | Some("25") => ExceptionClass::OperationalError,
+        Some("40") => ExceptionClass::TransactionRollbackError,
+        Some("XX") => ExceptionClass::InternalError,
+        _ => ExceptionClass::OperationalError,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_uses_dedicated_entries_over_class_prefix() {
+        assert_eq!(classify("23505"), ExceptionClass::UniqueViolation);
+        assert_eq!(classify("23503"), ExceptionClass::ForeignKeyViolation);
+        assert_eq!(classify("23502"), ExceptionClass::NotNullViolation);
+        assert_eq!(classify("40001"), ExceptionClass::SerializationFailure);
+        assert_eq!(classify("40P01"), ExceptionClass::DeadlockDetected);
+    }
+
+    #[test]
+    fn classify_falls_back_to_class_prefix() {
+        assert_eq!(classify("23514"), ExceptionClass::IntegrityError);
+        assert_eq!(classify("22001"), ExceptionClass::DataError);
+        assert_eq!(classify("42601"), ExceptionClass::ProgrammingError);
+        assert_eq!(classify("08006"), ExceptionClass::OperationalError);
+        assert_eq!(classify("40P99"), ExceptionClass::TransactionRollbackError);
+    }
+
+    #[test]
+    fn classify_invalid_transaction_state_is_operational_not_internal() {
+        // `25P02` ("in failed sql transaction") is "Invalid Transaction
+        // State", not Postgres's Internal Error class -- that's `XX000`.
+        assert_eq!(classify("25P02"), ExceptionClass::OperationalError);
+        assert_eq!(classify("25000"), ExceptionClass::OperationalError);
+    }
+
+    #[test]
+    fn classify_internal_error_class() {
+        assert_eq!(classify("XX000"), ExceptionClass::InternalError);
+        assert_eq!(classify("XX001"), ExceptionClass::InternalError);
+    }
+
+    #[test]
+    fn classify_unknown_class_defaults_to_operational_error() {
+        assert_eq!(classify("99999"), ExceptionClass::OperationalError);
+        assert_eq!(classify(""), ExceptionClass::OperationalError);
+    }
+}